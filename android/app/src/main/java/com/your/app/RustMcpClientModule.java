@@ -28,11 +28,22 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     // 已注册的事件回调
     private final Map<String, EventCallback> eventCallbacks = new HashMap<>();
     
+    // Rust端客户端句柄，0表示尚未初始化
+    private long clientHandle = 0;
+    
+    // Rust端多服务器管理器句柄，0表示尚未初始化
+    private long managerHandle = 0;
+    
     // 事件回调接口
     private interface EventCallback {
         void invoke(String jsonData);
     }
     
+    // 耗时调用的完成回调接口，在Rust运行时的工作线程上调用
+    private interface ResultCallback {
+        void invoke(@Nullable String resultJson);
+    }
+    
     static {
         // 加载Rust库
         System.loadLibrary("rust_mcp_client");
@@ -52,8 +63,17 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     @ReactMethod
     public void initialize(Promise promise) {
         try {
-            long result = initClient();
-            if (result != 0) {
+            // 重复初始化时释放旧客户端
+            if (clientHandle != 0) {
+                destroyClient(clientHandle);
+            }
+            
+            clientHandle = initClient();
+            if (clientHandle != 0) {
+                // 初始化前添加的监听器注册到新客户端
+                for (Map.Entry<String, EventCallback> entry : eventCallbacks.entrySet()) {
+                    registerEventCallback(clientHandle, entry.getKey(), entry.getValue());
+                }
                 promise.resolve(true);
             } else {
                 promise.reject("INIT_ERROR", "初始化MCP客户端失败");
@@ -63,12 +83,35 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
         }
     }
     
+    // 初始化多服务器管理器
+    @ReactMethod
+    public void initializeManager(Promise promise) {
+        try {
+            // 重复初始化时释放旧管理器
+            if (managerHandle != 0) {
+                destroyManager(managerHandle);
+            }
+            
+            managerHandle = initManager();
+            if (managerHandle != 0) {
+                // 各服务器的事件 (如连接状态) 通过管理器句柄发送，已添加的监听器同样注册到管理器
+                for (Map.Entry<String, EventCallback> entry : eventCallbacks.entrySet()) {
+                    registerEventCallback(managerHandle, entry.getKey(), entry.getValue());
+                }
+                promise.resolve(true);
+            } else {
+                promise.reject("INIT_MANAGER_ERROR", "初始化MCP管理器失败");
+            }
+        } catch (Exception e) {
+            promise.reject("INIT_MANAGER_ERROR", "初始化MCP管理器异常: " + e.getMessage());
+        }
+    }
+    
     // 连接到MCP服务器
     @ReactMethod
     public void connect(String serverUrl, Promise promise) {
         try {
-            boolean result = connect(serverUrl);
-            promise.resolve(result);
+            connect(clientHandle, serverUrl, errorJson -> resolveStatus(promise, errorJson));
         } catch (Exception e) {
             promise.reject("CONNECT_ERROR", "连接MCP服务器异常: " + e.getMessage());
         }
@@ -78,8 +121,7 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     @ReactMethod
     public void disconnect(Promise promise) {
         try {
-            boolean result = disconnect();
-            promise.resolve(result);
+            disconnect(clientHandle, errorJson -> resolveStatus(promise, errorJson));
         } catch (Exception e) {
             promise.reject("DISCONNECT_ERROR", "断开MCP服务器连接异常: " + e.getMessage());
         }
@@ -89,7 +131,7 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     @ReactMethod
     public void isConnected(Promise promise) {
         try {
-            boolean result = isConnected();
+            boolean result = isConnected(clientHandle);
             promise.resolve(result);
         } catch (Exception e) {
             promise.reject("CONNECTION_CHECK_ERROR", "检查MCP连接状态异常: " + e.getMessage());
@@ -100,8 +142,7 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     @ReactMethod
     public void callTool(String toolName, String parametersJson, Promise promise) {
         try {
-            String result = callTool(toolName, parametersJson);
-            promise.resolve(result);
+            callTool(clientHandle, toolName, parametersJson, promise::resolve);
         } catch (Exception e) {
             promise.reject("TOOL_CALL_ERROR", "调用MCP工具异常: " + e.getMessage());
         }
@@ -111,8 +152,7 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     @ReactMethod
     public void requestResource(String uri, Promise promise) {
         try {
-            String result = requestResource(uri);
-            promise.resolve(result);
+            requestResource(clientHandle, uri, promise::resolve);
         } catch (Exception e) {
             promise.reject("RESOURCE_REQUEST_ERROR", "请求MCP资源异常: " + e.getMessage());
         }
@@ -122,7 +162,7 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     @ReactMethod
     public void getServerInfo(Promise promise) {
         try {
-            String result = getServerInfo();
+            String result = getServerInfo(clientHandle);
             promise.resolve(result);
         } catch (Exception e) {
             promise.reject("SERVER_INFO_ERROR", "获取MCP服务器信息异常: " + e.getMessage());
@@ -133,13 +173,334 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
     @ReactMethod
     public void handleInput(String message, Promise promise) {
         try {
-            boolean result = handleInputFromRN(message);
+            boolean result = handleInputFromRN(clientHandle, message);
+            if (!result) {
+                reportLastError();
+            }
             promise.resolve(result);
         } catch (Exception e) {
             promise.reject("INPUT_HANDLING_ERROR", "处理MCP输入异常: " + e.getMessage());
         }
     }
     
+    // 设置首选线路格式 (json_rpc或tagged)，在下次连接时生效
+    @ReactMethod
+    public void setWireFormat(String format, Promise promise) {
+        try {
+            boolean result = setWireFormat(clientHandle, format);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("WIRE_FORMAT_ERROR", "设置线路格式异常: " + e.getMessage());
+        }
+    }
+    
+    // 取消进行中的工具调用或资源请求
+    @ReactMethod
+    public void cancel(String callId, Promise promise) {
+        try {
+            boolean result = cancel(clientHandle, callId);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("CANCEL_ERROR", "取消MCP调用异常: " + e.getMessage());
+        }
+    }
+    
+    // 设置单次请求的超时时间 (毫秒)
+    @ReactMethod
+    public void setRequestTimeout(double timeoutMs, Promise promise) {
+        try {
+            boolean result = setRequestTimeout(clientHandle, (long) timeoutMs);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("REQUEST_TIMEOUT_ERROR", "设置请求超时异常: " + e.getMessage());
+        }
+    }
+    
+    // 列出服务器提供的工具
+    @ReactMethod
+    public void listTools(Promise promise) {
+        try {
+            listTools(clientHandle, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("LIST_TOOLS_ERROR", "获取MCP工具列表异常: " + e.getMessage());
+        }
+    }
+    
+    // 列出服务器提供的资源
+    @ReactMethod
+    public void listResources(Promise promise) {
+        try {
+            listResources(clientHandle, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("LIST_RESOURCES_ERROR", "获取MCP资源列表异常: " + e.getMessage());
+        }
+    }
+    
+    // 列出服务器提供的资源模板
+    @ReactMethod
+    public void listResourceTemplates(Promise promise) {
+        try {
+            listResourceTemplates(clientHandle, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("LIST_RESOURCE_TEMPLATES_ERROR", "获取MCP资源模板列表异常: " + e.getMessage());
+        }
+    }
+    
+    // 按变量展开资源模板 (不需要客户端句柄)
+    @ReactMethod
+    public void expandResourceTemplate(String template, String variablesJson, Promise promise) {
+        try {
+            String result = expandResourceTemplate(template, variablesJson);
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("EXPAND_TEMPLATE_ERROR", "展开MCP资源模板异常: " + e.getMessage());
+        }
+    }
+    
+    // 订阅资源变更通知
+    @ReactMethod
+    public void subscribeResource(String uri, Promise promise) {
+        try {
+            subscribeResource(clientHandle, uri, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("SUBSCRIBE_ERROR", "订阅MCP资源异常: " + e.getMessage());
+        }
+    }
+    
+    // 取消订阅资源变更通知
+    @ReactMethod
+    public void unsubscribeResource(String uri, Promise promise) {
+        try {
+            unsubscribeResource(clientHandle, uri, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("UNSUBSCRIBE_ERROR", "取消订阅MCP资源异常: " + e.getMessage());
+        }
+    }
+    
+    // 列出服务器提供的提示词
+    @ReactMethod
+    public void listPrompts(Promise promise) {
+        try {
+            listPrompts(clientHandle, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("LIST_PROMPTS_ERROR", "获取MCP提示词列表异常: " + e.getMessage());
+        }
+    }
+    
+    // 获取提示词
+    @ReactMethod
+    public void getPrompt(String name, String argumentsJson, Promise promise) {
+        try {
+            getPrompt(clientHandle, name, argumentsJson, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("GET_PROMPT_ERROR", "获取MCP提示词异常: " + e.getMessage());
+        }
+    }
+    
+    // 将指定方法的服务器请求转发到RN处理，需在连接前调用
+    @ReactMethod
+    public void forwardServerRequests(String method, Promise promise) {
+        try {
+            boolean result = forwardServerRequests(clientHandle, method);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("FORWARD_REQUESTS_ERROR", "设置服务器请求转发异常: " + e.getMessage());
+        }
+    }
+    
+    // 回复转发到RN的服务器请求
+    @ReactMethod
+    public void respondToServerRequest(String requestId, String responseJson, Promise promise) {
+        try {
+            boolean result = respondToServerRequest(clientHandle, requestId, responseJson);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("SERVER_REQUEST_RESPONSE_ERROR", "回复服务器请求异常: " + e.getMessage());
+        }
+    }
+    
+    // 设置向服务器公开的根目录列表
+    @ReactMethod
+    public void setRoots(String rootsJson, Promise promise) {
+        try {
+            setRoots(clientHandle, rootsJson, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("SET_ROOTS_ERROR", "设置MCP根目录异常: " + e.getMessage());
+        }
+    }
+    
+    // 设置服务器日志级别
+    @ReactMethod
+    public void setServerLogLevel(String level, Promise promise) {
+        try {
+            setServerLogLevel(clientHandle, level, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("SET_LOG_LEVEL_ERROR", "设置服务器日志级别异常: " + e.getMessage());
+        }
+    }
+    
+    // 设置服务器日志的输出位置
+    @ReactMethod
+    public void setLogSinks(String sinksJson, Promise promise) {
+        try {
+            String result = setLogSinks(clientHandle, sinksJson);
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("SET_LOG_SINKS_ERROR", "设置服务器日志输出异常: " + e.getMessage());
+        }
+    }
+    
+    // 获取最近的服务器日志
+    @ReactMethod
+    public void getServerLogs(Promise promise) {
+        try {
+            String result = getServerLogs(clientHandle);
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("GET_SERVER_LOGS_ERROR", "获取服务器日志异常: " + e.getMessage());
+        }
+    }
+    
+    // 获取提示词或资源模板参数的补全建议
+    @ReactMethod
+    public void complete(String referenceJson, String argumentName, String partialValue, Promise promise) {
+        try {
+            complete(clientHandle, referenceJson, argumentName, partialValue, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("COMPLETE_ERROR", "获取MCP补全建议异常: " + e.getMessage());
+        }
+    }
+    
+    // 回复服务器的信息征询请求 (action为accept、decline或cancel)
+    @ReactMethod
+    public void respondToElicitation(String requestId, String action, String contentJson, Promise promise) {
+        try {
+            String result = respondToElicitation(clientHandle, requestId, action, contentJson);
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("ELICITATION_RESPONSE_ERROR", "回复信息征询异常: " + e.getMessage());
+        }
+    }
+    
+    // 设置健康检查间隔 (毫秒，0表示关闭) 和判定断开前允许的连续失败次数
+    @ReactMethod
+    public void setHealthCheck(double intervalMs, int failureThreshold, Promise promise) {
+        try {
+            boolean result = setHealthCheck(clientHandle, (long) intervalMs, failureThreshold);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("HEALTH_CHECK_ERROR", "设置健康检查异常: " + e.getMessage());
+        }
+    }
+    
+    // 获取连接健康状况
+    @ReactMethod
+    public void getConnectionHealth(Promise promise) {
+        try {
+            String result = getConnectionHealth(clientHandle);
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("CONNECTION_HEALTH_ERROR", "获取连接健康状况异常: " + e.getMessage());
+        }
+    }
+    
+    // 设置断线重连的最大重试次数 (0表示关闭)，以及重连后是否重发未完成的请求
+    @ReactMethod
+    public void setReconnect(int maxRetries, boolean replay, Promise promise) {
+        try {
+            boolean result = setReconnect(clientHandle, maxRetries, replay);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("RECONNECT_CONFIG_ERROR", "设置断线重连异常: " + e.getMessage());
+        }
+    }
+    
+    // 通过管理器连接服务器
+    @ReactMethod
+    public void connectServer(String serverId, String serverUrl, Promise promise) {
+        try {
+            connectServer(managerHandle, serverId, serverUrl, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("CONNECT_SERVER_ERROR", "连接MCP服务器异常: " + e.getMessage());
+        }
+    }
+    
+    // 断开并移除管理器中的服务器
+    @ReactMethod
+    public void removeServer(String serverId, Promise promise) {
+        try {
+            removeServer(managerHandle, serverId, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("REMOVE_SERVER_ERROR", "移除MCP服务器异常: " + e.getMessage());
+        }
+    }
+    
+    // 列出管理器中所有服务器的工具
+    @ReactMethod
+    public void listAllTools(Promise promise) {
+        try {
+            listAllTools(managerHandle, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("LIST_ALL_TOOLS_ERROR", "获取MCP工具列表异常: " + e.getMessage());
+        }
+    }
+    
+    // 通过管理器调用工具 (按工具名路由到对应服务器)
+    @ReactMethod
+    public void callServerTool(String toolName, String parametersJson, Promise promise) {
+        try {
+            callServerTool(managerHandle, toolName, parametersJson, promise::resolve);
+        } catch (Exception e) {
+            promise.reject("TOOL_CALL_ERROR", "调用MCP工具异常: " + e.getMessage());
+        }
+    }
+    
+    // 获取管理器中各服务器的连接状态
+    @ReactMethod
+    public void getServerStates(Promise promise) {
+        try {
+            String result = getServerStates(managerHandle);
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("SERVER_STATES_ERROR", "获取MCP服务器状态异常: " + e.getMessage());
+        }
+    }
+    
+    // 设置连接附加选项 ({"headers": {...}, "sseEndpoint": ..., "args": [...], "env": {...}, "workingDir": ...})，在下次连接时生效
+    @ReactMethod
+    public void setConnectOptions(String optionsJson, Promise promise) {
+        try {
+            boolean result = setConnectOptions(clientHandle, optionsJson);
+            if (!result) {
+                reportLastError();
+            }
+            promise.resolve(result);
+        } catch (Exception e) {
+            promise.reject("CONNECT_OPTIONS_ERROR", "设置连接选项异常: " + e.getMessage());
+        }
+    }
+    
     // 注册事件监听器
     @ReactMethod
     public void addListener(String eventName) {
@@ -151,8 +512,13 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
             
             eventCallbacks.put(eventName, callback);
             
-            // 注册到Rust端
-            registerEventCallback(eventName, callback);
+            // 注册到Rust端的客户端和管理器，尚未初始化的在initialize或initializeManager中注册
+            if (clientHandle != 0) {
+                registerEventCallback(clientHandle, eventName, callback);
+            }
+            if (managerHandle != 0) {
+                registerEventCallback(managerHandle, eventName, callback);
+            }
         }
     }
     
@@ -163,6 +529,37 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
         // 因为事件回调在Rust端被管理
     }
     
+    // 模块销毁时释放Rust端客户端和管理器
+    @Override
+    public void invalidate() {
+        if (clientHandle != 0) {
+            destroyClient(clientHandle);
+            clientHandle = 0;
+        }
+        if (managerHandle != 0) {
+            destroyManager(managerHandle);
+            managerHandle = 0;
+        }
+        super.invalidate();
+    }
+    
+    // 将Rust端记录的失败原因作为错误事件发送到RN
+    // (失败原因按线程记录，需在失败调用所在的线程上读取)
+    private void reportLastError() {
+        String errorJson = getLastError();
+        if (errorJson != null) {
+            sendEvent(getReactApplicationContext(), EVENT_ERROR, errorJson);
+        }
+    }
+    
+    // 完成返回bool的耗时调用，失败原因 (errorJson不为null) 作为错误事件发送到RN
+    private void resolveStatus(Promise promise, @Nullable String errorJson) {
+        if (errorJson != null) {
+            sendEvent(getReactApplicationContext(), EVENT_ERROR, errorJson);
+        }
+        promise.resolve(errorJson == null);
+    }
+    
     // 发送事件到RN
     private void sendEvent(ReactContext reactContext, String eventName, String jsonData) {
         if (reactContext.hasActiveCatalystInstance()) {
@@ -176,14 +573,46 @@ public class RustMcpClientModule extends ReactContextBaseJavaModule {
         }
     }
     
-    // JNI 方法声明
+    // JNI 方法声明 (除initClient、initManager、getLastError和expandResourceTemplate外都以句柄为第一个参数)
     private native long initClient();
-    private native boolean connect(String serverUrl);
-    private native boolean disconnect();
-    private native boolean isConnected();
-    private native String callTool(String toolName, String parametersJson);
-    private native String requestResource(String uri);
-    private native String getServerInfo();
-    private native boolean handleInputFromRN(String message);
-    private native boolean registerEventCallback(String eventName, EventCallback callback);
+    private native boolean destroyClient(long handle);
+    private native String getLastError();
+    private native void connect(long handle, String serverUrl, ResultCallback callback);
+    private native void disconnect(long handle, ResultCallback callback);
+    private native boolean isConnected(long handle);
+    private native void callTool(long handle, String toolName, String parametersJson, ResultCallback callback);
+    private native void requestResource(long handle, String uri, ResultCallback callback);
+    private native String getServerInfo(long handle);
+    private native boolean handleInputFromRN(long handle, String message);
+    private native boolean setWireFormat(long handle, String format);
+    private native boolean cancel(long handle, String callId);
+    private native boolean setRequestTimeout(long handle, long timeoutMs);
+    private native void listTools(long handle, ResultCallback callback);
+    private native void listResources(long handle, ResultCallback callback);
+    private native void listResourceTemplates(long handle, ResultCallback callback);
+    private native String expandResourceTemplate(String template, String variablesJson);
+    private native void subscribeResource(long handle, String uri, ResultCallback callback);
+    private native void unsubscribeResource(long handle, String uri, ResultCallback callback);
+    private native void listPrompts(long handle, ResultCallback callback);
+    private native void getPrompt(long handle, String name, String argumentsJson, ResultCallback callback);
+    private native boolean forwardServerRequests(long handle, String method);
+    private native boolean respondToServerRequest(long handle, String requestId, String responseJson);
+    private native void setRoots(long handle, String rootsJson, ResultCallback callback);
+    private native void setServerLogLevel(long handle, String level, ResultCallback callback);
+    private native String setLogSinks(long handle, String sinksJson);
+    private native String getServerLogs(long handle);
+    private native void complete(long handle, String referenceJson, String argumentName, String partialValue, ResultCallback callback);
+    private native String respondToElicitation(long handle, String requestId, String action, String contentJson);
+    private native boolean setHealthCheck(long handle, long intervalMs, int failureThreshold);
+    private native String getConnectionHealth(long handle);
+    private native boolean setReconnect(long handle, int maxRetries, boolean replay);
+    private native long initManager();
+    private native boolean destroyManager(long handle);
+    private native void connectServer(long handle, String serverId, String serverUrl, ResultCallback callback);
+    private native void removeServer(long handle, String serverId, ResultCallback callback);
+    private native void listAllTools(long handle, ResultCallback callback);
+    private native void callServerTool(long handle, String toolName, String parametersJson, ResultCallback callback);
+    private native String getServerStates(long handle);
+    private native boolean setConnectOptions(long handle, String optionsJson);
+    private native boolean registerEventCallback(long handle, String eventName, EventCallback callback);
 } 
\ No newline at end of file
@@ -1,28 +1,89 @@
 #import "RustMcpClient.h"
 
-// 声明Rust FFI函数
-extern bool mcp_init_client(void);
-extern bool mcp_connect(const char* server_url);
-extern bool mcp_disconnect(void);
-extern bool mcp_is_connected(void);
-extern char* mcp_call_tool(const char* tool_name, const char* parameters_json);
-extern char* mcp_request_resource(const char* uri);
-extern char* mcp_get_server_info(void);
-extern bool mcp_handle_input_from_rn(const char* message);
+// 声明Rust FFI函数 (除mcp_init_client、mcp_init_manager、mcp_get_last_error和mcp_expand_resource_template外都以句柄为第一个参数)
+extern uint64_t mcp_init_client(void);
+extern bool mcp_destroy_client(uint64_t handle);
+extern char* mcp_get_last_error(void);
+extern void mcp_connect(uint64_t handle, const char* server_url, void (*completion)(const char*, void*), void* context);
+extern void mcp_disconnect(uint64_t handle, void (*completion)(const char*, void*), void* context);
+extern bool mcp_is_connected(uint64_t handle);
+extern void mcp_call_tool(uint64_t handle, const char* tool_name, const char* parameters_json, void (*completion)(const char*, void*), void* context);
+extern void mcp_request_resource(uint64_t handle, const char* uri, void (*completion)(const char*, void*), void* context);
+extern char* mcp_get_server_info(uint64_t handle);
+extern bool mcp_handle_input_from_rn(uint64_t handle, const char* message);
+extern bool mcp_set_wire_format(uint64_t handle, const char* format);
+extern bool mcp_cancel(uint64_t handle, const char* call_id);
+extern bool mcp_set_request_timeout(uint64_t handle, uint64_t timeout_ms);
+extern void mcp_list_tools(uint64_t handle, void (*completion)(const char*, void*), void* context);
+extern void mcp_list_resources(uint64_t handle, void (*completion)(const char*, void*), void* context);
+extern void mcp_list_resource_templates(uint64_t handle, void (*completion)(const char*, void*), void* context);
+extern char* mcp_expand_resource_template(const char* template, const char* variables_json);
+extern void mcp_subscribe_resource(uint64_t handle, const char* uri, void (*completion)(const char*, void*), void* context);
+extern void mcp_unsubscribe_resource(uint64_t handle, const char* uri, void (*completion)(const char*, void*), void* context);
+extern void mcp_list_prompts(uint64_t handle, void (*completion)(const char*, void*), void* context);
+extern void mcp_get_prompt(uint64_t handle, const char* name, const char* arguments_json, void (*completion)(const char*, void*), void* context);
+extern bool mcp_forward_server_requests(uint64_t handle, const char* method);
+extern bool mcp_respond_to_server_request(uint64_t handle, const char* request_id, const char* response_json);
+extern void mcp_set_roots(uint64_t handle, const char* roots_json, void (*completion)(const char*, void*), void* context);
+extern void mcp_set_server_log_level(uint64_t handle, const char* level, void (*completion)(const char*, void*), void* context);
+extern char* mcp_set_log_sinks(uint64_t handle, const char* sinks_json);
+extern char* mcp_get_server_logs(uint64_t handle);
+extern void mcp_complete(uint64_t handle, const char* reference_json, const char* argument_name, const char* partial_value, void (*completion)(const char*, void*), void* context);
+extern char* mcp_respond_to_elicitation(uint64_t handle, const char* request_id, const char* action, const char* content_json);
+extern bool mcp_set_health_check(uint64_t handle, uint64_t interval_ms, uint32_t failure_threshold);
+extern char* mcp_get_connection_health(uint64_t handle);
+extern bool mcp_set_reconnect(uint64_t handle, uint32_t max_retries, bool replay);
+extern uint64_t mcp_init_manager(void);
+extern bool mcp_destroy_manager(uint64_t handle);
+extern void mcp_connect_server(uint64_t handle, const char* server_id, const char* server_url, void (*completion)(const char*, void*), void* context);
+extern void mcp_remove_server(uint64_t handle, const char* server_id, void (*completion)(const char*, void*), void* context);
+extern void mcp_list_all_tools(uint64_t handle, void (*completion)(const char*, void*), void* context);
+extern void mcp_call_server_tool(uint64_t handle, const char* tool_name, const char* parameters_json, void (*completion)(const char*, void*), void* context);
+extern char* mcp_get_server_states(uint64_t handle);
+extern bool mcp_set_connect_options(uint64_t handle, const char* options_json);
 extern void mcp_free_string(char* ptr);
-extern bool mcp_register_event_callback(const char* event_name, void (*callback)(const char*, void*), void* context);
+extern bool mcp_register_event_callback(uint64_t handle, const char* event_name, void (*callback)(const char*, void*), void* context);
 
-// 事件名称常量
+// 耗时调用的完成回调，返回bool的调用成功时resultJson为nil
+typedef void (^McpCompletionBlock)(NSString *resultJson);
+
+// Rust端在运行时线程上调用的完成函数，上下文为completionContext持有的block，每次调用恰好回调一次
+static void invokeCompletion(const char* resultJson, void* context) {
+    McpCompletionBlock completion = (__bridge_transfer McpCompletionBlock)context;
+    completion(resultJson != NULL ? [NSString stringWithUTF8String:resultJson] : nil);
+}
+
+// 持有完成block并转为传给Rust端的上下文，在invokeCompletion中释放
+static void* completionContext(McpCompletionBlock completion) {
+    return (__bridge_retained void*)[completion copy];
+}
+
+// 事件名称常量 (Rust端按McpEvent的变体名路由，事件名为mcp加变体名)
 static NSString* const EVENT_CONNECTION_STATE = @"mcpConnectionState";
 static NSString* const EVENT_TOOL_CALL = @"mcpToolCall";
 static NSString* const EVENT_RESOURCE_REQUEST = @"mcpResourceRequest";
 static NSString* const EVENT_ERROR = @"mcpError";
+static NSString* const EVENT_TOOLS_CHANGED = @"mcpToolsChanged";
+static NSString* const EVENT_RESOURCES_CHANGED = @"mcpResourcesChanged";
+static NSString* const EVENT_RESOURCE_UPDATED = @"mcpResourceUpdated";
+static NSString* const EVENT_PROMPTS_CHANGED = @"mcpPromptsChanged";
+static NSString* const EVENT_SERVER_REQUEST = @"mcpServerRequest";
+static NSString* const EVENT_SERVER_REQUEST_CANCELLED = @"mcpServerRequestCancelled";
+static NSString* const EVENT_SERVER_LOG = @"mcpServerLog";
+static NSString* const EVENT_PROGRESS = @"mcpProgress";
+static NSString* const EVENT_ELICITATION = @"mcpElicitation";
+static NSString* const EVENT_RECONNECTING = @"mcpReconnecting";
+static NSString* const EVENT_RECONNECTED = @"mcpReconnected";
 
 // 注册的事件名称列表
 static NSArray<NSString*>* eventNames;
 
 @implementation RustMcpClient {
     bool hasListeners;
+    // Rust端客户端句柄，0表示尚未初始化
+    uint64_t clientHandle;
+    // Rust端多服务器管理器句柄，0表示尚未初始化
+    uint64_t managerHandle;
 }
 
 RCT_EXPORT_MODULE()
@@ -33,7 +94,18 @@ RCT_EXPORT_MODULE()
             EVENT_CONNECTION_STATE,
             EVENT_TOOL_CALL,
             EVENT_RESOURCE_REQUEST,
-            EVENT_ERROR
+            EVENT_ERROR,
+            EVENT_TOOLS_CHANGED,
+            EVENT_RESOURCES_CHANGED,
+            EVENT_RESOURCE_UPDATED,
+            EVENT_PROMPTS_CHANGED,
+            EVENT_SERVER_REQUEST,
+            EVENT_SERVER_REQUEST_CANCELLED,
+            EVENT_SERVER_LOG,
+            EVENT_PROGRESS,
+            EVENT_ELICITATION,
+            EVENT_RECONNECTING,
+            EVENT_RECONNECTED
         ];
     }
 }
@@ -47,9 +119,10 @@ RCT_EXPORT_MODULE()
 - (void)startObserving {
     hasListeners = YES;
     
-    // 当首次有JS需要监听事件时，可以注册所有需要的回调
+    // 当首次有JS需要监听事件时，为客户端和管理器注册所有需要的回调
     for (NSString *eventName in eventNames) {
-        [self registerCallbackForEvent:eventName];
+        [self registerCallbackForEvent:eventName handle:clientHandle];
+        [self registerCallbackForEvent:eventName handle:managerHandle];
     }
 }
 
@@ -58,11 +131,25 @@ RCT_EXPORT_MODULE()
     // 当没有监听者时，可以清理资源
 }
 
+// 模块销毁时释放Rust端客户端和管理器
+- (void)invalidate {
+    if (clientHandle != 0) {
+        mcp_destroy_client(clientHandle);
+        clientHandle = 0;
+    }
+    if (managerHandle != 0) {
+        mcp_destroy_manager(managerHandle);
+        managerHandle = 0;
+    }
+    [super invalidate];
+}
+
 #pragma mark - 辅助方法
 
-// 注册事件回调
-- (void)registerCallbackForEvent:(NSString *)eventName {
-    if (!hasListeners) return;
+// 为客户端或管理器句柄注册事件回调
+- (void)registerCallbackForEvent:(NSString *)eventName handle:(uint64_t)handle {
+    // 尚未初始化时在initialize或initializeManager中注册
+    if (!hasListeners || handle == 0) return;
     
     // 创建自定义上下文，包含事件名称和self引用
     RustMcpClient * __weak weakSelf = self;
@@ -81,7 +168,24 @@ RCT_EXPORT_MODULE()
     void* context = (__bridge void*)self;
     
     // 注册回调
-    mcp_register_event_callback([eventName UTF8String], callback, context);
+    mcp_register_event_callback(handle, [eventName UTF8String], callback, context);
+}
+
+// 将Rust端记录的失败原因作为错误事件发送到RN
+// (失败原因按线程记录，需在失败调用所在的线程上读取)
+- (void)reportLastError {
+    NSString *errorJson = [self getStringAndFree:mcp_get_last_error()];
+    if (errorJson != nil && hasListeners) {
+        [self sendEventWithName:EVENT_ERROR body:errorJson];
+    }
+}
+
+// 完成返回bool的耗时调用，失败原因 (errorJson不为nil) 作为错误事件发送到RN
+- (void)resolveStatus:(RCTPromiseResolveBlock)resolve errorJson:(NSString *)errorJson {
+    if (errorJson != nil && hasListeners) {
+        [self sendEventWithName:EVENT_ERROR body:errorJson];
+    }
+    resolve(@(errorJson == nil));
 }
 
 // 释放Rust字符串
@@ -101,20 +205,49 @@ RCT_EXPORT_MODULE()
 RCT_EXPORT_METHOD(initialize:(RCTPromiseResolveBlock)resolve
                   reject:(RCTPromiseRejectBlock)reject) {
     @try {
-        bool result = mcp_init_client();
-        resolve(@(result));
+        // 重复初始化时释放旧客户端
+        if (clientHandle != 0) {
+            mcp_destroy_client(clientHandle);
+        }
+        
+        clientHandle = mcp_init_client();
+        for (NSString *eventName in eventNames) {
+            [self registerCallbackForEvent:eventName handle:clientHandle];
+        }
+        resolve(@(clientHandle != 0));
     } @catch (NSException *exception) {
         reject(@"INIT_ERROR", [NSString stringWithFormat:@"初始化MCP客户端异常: %@", exception.reason], nil);
     }
 }
 
+// 初始化多服务器管理器
+RCT_EXPORT_METHOD(initializeManager:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        // 重复初始化时释放旧管理器
+        if (managerHandle != 0) {
+            mcp_destroy_manager(managerHandle);
+        }
+        
+        managerHandle = mcp_init_manager();
+        // 各服务器的事件 (如连接状态) 通过管理器句柄发送
+        for (NSString *eventName in eventNames) {
+            [self registerCallbackForEvent:eventName handle:managerHandle];
+        }
+        resolve(@(managerHandle != 0));
+    } @catch (NSException *exception) {
+        reject(@"INIT_MANAGER_ERROR", [NSString stringWithFormat:@"初始化MCP管理器异常: %@", exception.reason], nil);
+    }
+}
+
 // 连接到MCP服务器
 RCT_EXPORT_METHOD(connect:(NSString *)serverUrl
                   resolver:(RCTPromiseResolveBlock)resolve
                   rejecter:(RCTPromiseRejectBlock)reject) {
     @try {
-        bool result = mcp_connect([serverUrl UTF8String]);
-        resolve(@(result));
+        mcp_connect(clientHandle, [serverUrl UTF8String], invokeCompletion, completionContext(^(NSString *errorJson) {
+            [self resolveStatus:resolve errorJson:errorJson];
+        }));
     } @catch (NSException *exception) {
         reject(@"CONNECT_ERROR", [NSString stringWithFormat:@"连接MCP服务器异常: %@", exception.reason], nil);
     }
@@ -124,8 +257,9 @@ RCT_EXPORT_METHOD(connect:(NSString *)serverUrl
 RCT_EXPORT_METHOD(disconnect:(RCTPromiseResolveBlock)resolve
                   reject:(RCTPromiseRejectBlock)reject) {
     @try {
-        bool result = mcp_disconnect();
-        resolve(@(result));
+        mcp_disconnect(clientHandle, invokeCompletion, completionContext(^(NSString *errorJson) {
+            [self resolveStatus:resolve errorJson:errorJson];
+        }));
     } @catch (NSException *exception) {
         reject(@"DISCONNECT_ERROR", [NSString stringWithFormat:@"断开MCP服务器连接异常: %@", exception.reason], nil);
     }
@@ -135,7 +269,7 @@ RCT_EXPORT_METHOD(disconnect:(RCTPromiseResolveBlock)resolve
 RCT_EXPORT_METHOD(isConnected:(RCTPromiseResolveBlock)resolve
                   reject:(RCTPromiseRejectBlock)reject) {
     @try {
-        bool result = mcp_is_connected();
+        bool result = mcp_is_connected(clientHandle);
         resolve(@(result));
     } @catch (NSException *exception) {
         reject(@"CONNECTION_CHECK_ERROR", [NSString stringWithFormat:@"检查MCP连接状态异常: %@", exception.reason], nil);
@@ -148,9 +282,9 @@ RCT_EXPORT_METHOD(callTool:(NSString *)toolName
                   resolver:(RCTPromiseResolveBlock)resolve
                   rejecter:(RCTPromiseRejectBlock)reject) {
     @try {
-        char* result = mcp_call_tool([toolName UTF8String], [parametersJson UTF8String]);
-        NSString *jsonResult = [self getStringAndFree:result];
-        resolve(jsonResult);
+        mcp_call_tool(clientHandle, [toolName UTF8String], [parametersJson UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
     } @catch (NSException *exception) {
         reject(@"TOOL_CALL_ERROR", [NSString stringWithFormat:@"调用MCP工具异常: %@", exception.reason], nil);
     }
@@ -161,9 +295,9 @@ RCT_EXPORT_METHOD(requestResource:(NSString *)uri
                   resolver:(RCTPromiseResolveBlock)resolve
                   rejecter:(RCTPromiseRejectBlock)reject) {
     @try {
-        char* result = mcp_request_resource([uri UTF8String]);
-        NSString *jsonResult = [self getStringAndFree:result];
-        resolve(jsonResult);
+        mcp_request_resource(clientHandle, [uri UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
     } @catch (NSException *exception) {
         reject(@"RESOURCE_REQUEST_ERROR", [NSString stringWithFormat:@"请求MCP资源异常: %@", exception.reason], nil);
     }
@@ -173,7 +307,7 @@ RCT_EXPORT_METHOD(requestResource:(NSString *)uri
 RCT_EXPORT_METHOD(getServerInfo:(RCTPromiseResolveBlock)resolve
                   reject:(RCTPromiseRejectBlock)reject) {
     @try {
-        char* result = mcp_get_server_info();
+        char* result = mcp_get_server_info(clientHandle);
         NSString *jsonResult = [self getStringAndFree:result];
         resolve(jsonResult);
     } @catch (NSException *exception) {
@@ -186,11 +320,397 @@ RCT_EXPORT_METHOD(handleInput:(NSString *)message
                   resolver:(RCTPromiseResolveBlock)resolve
                   rejecter:(RCTPromiseRejectBlock)reject) {
     @try {
-        bool result = mcp_handle_input_from_rn([message UTF8String]);
+        bool result = mcp_handle_input_from_rn(clientHandle, [message UTF8String]);
+        if (!result) {
+            [self reportLastError];
+        }
         resolve(@(result));
     } @catch (NSException *exception) {
         reject(@"INPUT_HANDLING_ERROR", [NSString stringWithFormat:@"处理MCP输入异常: %@", exception.reason], nil);
     }
 }
 
+// 设置首选线路格式 (json_rpc或tagged)，在下次连接时生效
+RCT_EXPORT_METHOD(setWireFormat:(NSString *)format
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_set_wire_format(clientHandle, [format UTF8String]);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"WIRE_FORMAT_ERROR", [NSString stringWithFormat:@"设置线路格式异常: %@", exception.reason], nil);
+    }
+}
+
+// 取消进行中的工具调用或资源请求
+RCT_EXPORT_METHOD(cancel:(NSString *)callId
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_cancel(clientHandle, [callId UTF8String]);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"CANCEL_ERROR", [NSString stringWithFormat:@"取消MCP调用异常: %@", exception.reason], nil);
+    }
+}
+
+// 设置单次请求的超时时间 (毫秒)
+RCT_EXPORT_METHOD(setRequestTimeout:(double)timeoutMs
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_set_request_timeout(clientHandle, (uint64_t)timeoutMs);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"REQUEST_TIMEOUT_ERROR", [NSString stringWithFormat:@"设置请求超时异常: %@", exception.reason], nil);
+    }
+}
+
+// 列出服务器提供的工具
+RCT_EXPORT_METHOD(listTools:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_list_tools(clientHandle, invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"LIST_TOOLS_ERROR", [NSString stringWithFormat:@"获取MCP工具列表异常: %@", exception.reason], nil);
+    }
+}
+
+// 列出服务器提供的资源
+RCT_EXPORT_METHOD(listResources:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_list_resources(clientHandle, invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"LIST_RESOURCES_ERROR", [NSString stringWithFormat:@"获取MCP资源列表异常: %@", exception.reason], nil);
+    }
+}
+
+// 列出服务器提供的资源模板
+RCT_EXPORT_METHOD(listResourceTemplates:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_list_resource_templates(clientHandle, invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"LIST_RESOURCE_TEMPLATES_ERROR", [NSString stringWithFormat:@"获取MCP资源模板列表异常: %@", exception.reason], nil);
+    }
+}
+
+// 按变量展开资源模板 (不需要客户端句柄)
+RCT_EXPORT_METHOD(expandResourceTemplate:(NSString *)template
+                  variablesJson:(NSString *)variablesJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        char* result = mcp_expand_resource_template([template UTF8String], [variablesJson UTF8String]);
+        NSString *jsonResult = [self getStringAndFree:result];
+        resolve(jsonResult);
+    } @catch (NSException *exception) {
+        reject(@"EXPAND_TEMPLATE_ERROR", [NSString stringWithFormat:@"展开MCP资源模板异常: %@", exception.reason], nil);
+    }
+}
+
+// 订阅资源变更通知
+RCT_EXPORT_METHOD(subscribeResource:(NSString *)uri
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_subscribe_resource(clientHandle, [uri UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"SUBSCRIBE_ERROR", [NSString stringWithFormat:@"订阅MCP资源异常: %@", exception.reason], nil);
+    }
+}
+
+// 取消订阅资源变更通知
+RCT_EXPORT_METHOD(unsubscribeResource:(NSString *)uri
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_unsubscribe_resource(clientHandle, [uri UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"UNSUBSCRIBE_ERROR", [NSString stringWithFormat:@"取消订阅MCP资源异常: %@", exception.reason], nil);
+    }
+}
+
+// 列出服务器提供的提示词
+RCT_EXPORT_METHOD(listPrompts:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_list_prompts(clientHandle, invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"LIST_PROMPTS_ERROR", [NSString stringWithFormat:@"获取MCP提示词列表异常: %@", exception.reason], nil);
+    }
+}
+
+// 获取提示词
+RCT_EXPORT_METHOD(getPrompt:(NSString *)name
+                  argumentsJson:(NSString *)argumentsJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_get_prompt(clientHandle, [name UTF8String], [argumentsJson UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"GET_PROMPT_ERROR", [NSString stringWithFormat:@"获取MCP提示词异常: %@", exception.reason], nil);
+    }
+}
+
+// 将指定方法的服务器请求转发到RN处理，需在连接前调用
+RCT_EXPORT_METHOD(forwardServerRequests:(NSString *)method
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_forward_server_requests(clientHandle, [method UTF8String]);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"FORWARD_REQUESTS_ERROR", [NSString stringWithFormat:@"设置服务器请求转发异常: %@", exception.reason], nil);
+    }
+}
+
+// 回复转发到RN的服务器请求
+RCT_EXPORT_METHOD(respondToServerRequest:(NSString *)requestId
+                  responseJson:(NSString *)responseJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_respond_to_server_request(clientHandle, [requestId UTF8String], [responseJson UTF8String]);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"SERVER_REQUEST_RESPONSE_ERROR", [NSString stringWithFormat:@"回复服务器请求异常: %@", exception.reason], nil);
+    }
+}
+
+// 设置向服务器公开的根目录列表
+RCT_EXPORT_METHOD(setRoots:(NSString *)rootsJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_set_roots(clientHandle, [rootsJson UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"SET_ROOTS_ERROR", [NSString stringWithFormat:@"设置MCP根目录异常: %@", exception.reason], nil);
+    }
+}
+
+// 设置服务器日志级别
+RCT_EXPORT_METHOD(setServerLogLevel:(NSString *)level
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_set_server_log_level(clientHandle, [level UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"SET_LOG_LEVEL_ERROR", [NSString stringWithFormat:@"设置服务器日志级别异常: %@", exception.reason], nil);
+    }
+}
+
+// 设置服务器日志的输出位置
+RCT_EXPORT_METHOD(setLogSinks:(NSString *)sinksJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        char* result = mcp_set_log_sinks(clientHandle, [sinksJson UTF8String]);
+        NSString *jsonResult = [self getStringAndFree:result];
+        resolve(jsonResult);
+    } @catch (NSException *exception) {
+        reject(@"SET_LOG_SINKS_ERROR", [NSString stringWithFormat:@"设置服务器日志输出异常: %@", exception.reason], nil);
+    }
+}
+
+// 获取最近的服务器日志
+RCT_EXPORT_METHOD(getServerLogs:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        char* result = mcp_get_server_logs(clientHandle);
+        NSString *jsonResult = [self getStringAndFree:result];
+        resolve(jsonResult);
+    } @catch (NSException *exception) {
+        reject(@"GET_SERVER_LOGS_ERROR", [NSString stringWithFormat:@"获取服务器日志异常: %@", exception.reason], nil);
+    }
+}
+
+// 获取提示词或资源模板参数的补全建议
+RCT_EXPORT_METHOD(complete:(NSString *)referenceJson
+                  argumentName:(NSString *)argumentName
+                  partialValue:(NSString *)partialValue
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_complete(clientHandle, [referenceJson UTF8String], [argumentName UTF8String], [partialValue UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"COMPLETE_ERROR", [NSString stringWithFormat:@"获取MCP补全建议异常: %@", exception.reason], nil);
+    }
+}
+
+// 回复服务器的信息征询请求 (action为accept、decline或cancel)
+RCT_EXPORT_METHOD(respondToElicitation:(NSString *)requestId
+                  action:(NSString *)action
+                  contentJson:(NSString *)contentJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        char* result = mcp_respond_to_elicitation(clientHandle, [requestId UTF8String], [action UTF8String], [contentJson UTF8String]);
+        NSString *jsonResult = [self getStringAndFree:result];
+        resolve(jsonResult);
+    } @catch (NSException *exception) {
+        reject(@"ELICITATION_RESPONSE_ERROR", [NSString stringWithFormat:@"回复信息征询异常: %@", exception.reason], nil);
+    }
+}
+
+// 设置健康检查间隔 (毫秒，0表示关闭) 和判定断开前允许的连续失败次数
+RCT_EXPORT_METHOD(setHealthCheck:(double)intervalMs
+                  failureThreshold:(NSInteger)failureThreshold
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_set_health_check(clientHandle, (uint64_t)intervalMs, (uint32_t)failureThreshold);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"HEALTH_CHECK_ERROR", [NSString stringWithFormat:@"设置健康检查异常: %@", exception.reason], nil);
+    }
+}
+
+// 获取连接健康状况
+RCT_EXPORT_METHOD(getConnectionHealth:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        char* result = mcp_get_connection_health(clientHandle);
+        NSString *jsonResult = [self getStringAndFree:result];
+        resolve(jsonResult);
+    } @catch (NSException *exception) {
+        reject(@"CONNECTION_HEALTH_ERROR", [NSString stringWithFormat:@"获取连接健康状况异常: %@", exception.reason], nil);
+    }
+}
+
+// 设置断线重连的最大重试次数 (0表示关闭)，以及重连后是否重发未完成的请求
+RCT_EXPORT_METHOD(setReconnect:(NSInteger)maxRetries
+                  replay:(BOOL)replay
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_set_reconnect(clientHandle, (uint32_t)maxRetries, replay);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"RECONNECT_CONFIG_ERROR", [NSString stringWithFormat:@"设置断线重连异常: %@", exception.reason], nil);
+    }
+}
+
+// 通过管理器连接服务器
+RCT_EXPORT_METHOD(connectServer:(NSString *)serverId
+                  serverUrl:(NSString *)serverUrl
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_connect_server(managerHandle, [serverId UTF8String], [serverUrl UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"CONNECT_SERVER_ERROR", [NSString stringWithFormat:@"连接MCP服务器异常: %@", exception.reason], nil);
+    }
+}
+
+// 断开并移除管理器中的服务器
+RCT_EXPORT_METHOD(removeServer:(NSString *)serverId
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_remove_server(managerHandle, [serverId UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"REMOVE_SERVER_ERROR", [NSString stringWithFormat:@"移除MCP服务器异常: %@", exception.reason], nil);
+    }
+}
+
+// 列出管理器中所有服务器的工具
+RCT_EXPORT_METHOD(listAllTools:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_list_all_tools(managerHandle, invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"LIST_ALL_TOOLS_ERROR", [NSString stringWithFormat:@"获取MCP工具列表异常: %@", exception.reason], nil);
+    }
+}
+
+// 通过管理器调用工具 (按工具名路由到对应服务器)
+RCT_EXPORT_METHOD(callServerTool:(NSString *)toolName
+                  parametersJson:(NSString *)parametersJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        mcp_call_server_tool(managerHandle, [toolName UTF8String], [parametersJson UTF8String], invokeCompletion, completionContext(^(NSString *resultJson) {
+            resolve(resultJson);
+        }));
+    } @catch (NSException *exception) {
+        reject(@"TOOL_CALL_ERROR", [NSString stringWithFormat:@"调用MCP工具异常: %@", exception.reason], nil);
+    }
+}
+
+// 获取管理器中各服务器的连接状态
+RCT_EXPORT_METHOD(getServerStates:(RCTPromiseResolveBlock)resolve
+                  reject:(RCTPromiseRejectBlock)reject) {
+    @try {
+        char* result = mcp_get_server_states(managerHandle);
+        NSString *jsonResult = [self getStringAndFree:result];
+        resolve(jsonResult);
+    } @catch (NSException *exception) {
+        reject(@"SERVER_STATES_ERROR", [NSString stringWithFormat:@"获取MCP服务器状态异常: %@", exception.reason], nil);
+    }
+}
+
+// 设置连接附加选项 ({"headers": {...}, "sseEndpoint": ..., "args": [...], "env": {...}, "workingDir": ...})，在下次连接时生效
+RCT_EXPORT_METHOD(setConnectOptions:(NSString *)optionsJson
+                  resolver:(RCTPromiseResolveBlock)resolve
+                  rejecter:(RCTPromiseRejectBlock)reject) {
+    @try {
+        bool result = mcp_set_connect_options(clientHandle, [optionsJson UTF8String]);
+        if (!result) {
+            [self reportLastError];
+        }
+        resolve(@(result));
+    } @catch (NSException *exception) {
+        reject(@"CONNECT_OPTIONS_ERROR", [NSString stringWithFormat:@"设置连接选项异常: %@", exception.reason], nil);
+    }
+}
+
 @end 
\ No newline at end of file
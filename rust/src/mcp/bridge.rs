@@ -1,71 +1,292 @@
-use std::sync::{Arc, Mutex};
-use crate::mcp::client::{McpClient, McpError, RNCallback};
-use crate::mcp::protocol::{McpMessage, McpResource, McpResponse, parse_mcp_message};
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use crate::mcp::client::{
+    HealthCheckConfig, InFlightPolicy, LogSink, McpClient, McpError, RNCallback, ReconnectConfig, RetryConfig,
+};
+use crate::mcp::jsonrpc::WireFormat;
+use crate::mcp::manager::McpClientManager;
+use crate::mcp::protocol::{CompletionReference, ElicitAction, ElicitResult, LoggingLevel, McpRoot};
+use crate::mcp::registry::{Handle, HandleEntry, HandleError, HandleKind, HandleRegistry};
+use crate::mcp::transport::ConnectOptions;
+use crate::mcp::uri_template::expand_uri_template;
 use std::collections::HashMap;
+use std::future::Future;
+use serde::Serialize;
 use serde_json::{json, Value};
+use tokio::runtime::Runtime;
 
 // React Native FFI 依赖
 #[cfg(target_os = "android")]
 use jni::JNIEnv;
 #[cfg(target_os = "android")]
-use jni::objects::{JClass, JObject, JString};
+use jni::objects::{JClass, JObject, JString, JValue};
 #[cfg(target_os = "android")]
-use jni::sys::{jboolean, jlong, jobject, jstring};
+use jni::sys::{jboolean, jint, jlong, jstring};
 
 #[cfg(target_os = "ios")]
 use std::os::raw::{c_char, c_void};
 #[cfg(target_os = "ios")]
 use std::ffi::{CStr, CString};
 
-// 全局客户端实例
+// 桥接层持有的异步运行时，所有FFI入口都通过它驱动异步调用
+//
+// 需要等待服务器响应的调用在运行时中执行，完成后通过回调返回结果，不阻塞调用方线程；
+// 其余入口只读写本地状态，直接在调用方线程上完成
 lazy_static::lazy_static! {
-    static ref MCP_CLIENT: Arc<Mutex<Option<McpClient>>> = Arc::new(Mutex::new(None));
+    static ref RUNTIME: Runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("mcp-runtime")
+        .build()
+        .expect("创建异步运行时失败");
+}
+
+// 句柄注册表，FFI入口只通过句柄访问客户端和管理器
+lazy_static::lazy_static! {
+    static ref HANDLES: RwLock<HandleRegistry> = RwLock::new(HandleRegistry::new());
 }
 
 // 定义回调类型
-type RnCallbackFn = Box<dyn Fn(String) -> () + Send + Sync>;
+type RnCallbackFn = Arc<dyn Fn(String) + Send + Sync>;
 
-// 全局事件回调
+// 全局事件回调，按句柄和事件名登记，同一事件重复注册时替换旧回调
 lazy_static::lazy_static! {
-    static ref EVENT_CALLBACKS: Arc<Mutex<HashMap<String, RnCallbackFn>>> = Arc::new(Mutex::new(HashMap::new()));
+    static ref EVENT_CALLBACKS: Arc<Mutex<HashMap<(Handle, String), RnCallbackFn>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+// 添加事件回调，句柄指向的客户端或管理器随后发出的该事件都通过该回调发送
+//
+// 客户端的回调在创建句柄时已指向事件分发，这里不获取客户端的锁，连接等耗时调用进行中也能注册
+fn register_event_callback(handle: Handle, event_name: &str, callback: RnCallbackFn) -> bool {
+    if let Err(err) = HANDLES.read().unwrap().get(handle) {
+        set_last_error(handle_error_json(&err));
+        return false;
+    }
+    
+    EVENT_CALLBACKS.lock().unwrap().insert((handle, event_name.to_string()), callback);
+    true
 }
 
-// 添加事件回调
-fn register_event_callback(event_name: &str, callback: RnCallbackFn) {
-    let mut callbacks = EVENT_CALLBACKS.lock().unwrap();
-    callbacks.insert(event_name.to_string(), callback);
+// 生成将事件分发到句柄回调的RN回调
+fn event_dispatcher(handle: Handle) -> RNCallback {
+    RNCallback {
+        callback_id: format!("rn_callback_{}", handle),
+        handler: Arc::new(move |json| {
+            emit_event(handle, &json);
+        }),
+    }
+}
+
+// 事件JSON对应的RN事件名
+//
+// 事件名为`mcp`加McpEvent的变体名 (如mcpConnectionState)；
+// 管理器转发的事件形如{"server_id": ..., "event": {...}}，按其中的事件确定
+fn event_name(data: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(data).ok()?;
+    let event = value.get("event").unwrap_or(&value);
+    let variant = event.as_object()?.keys().next()?;
+    Some(format!("mcp{}", variant))
 }
 
 // 触发事件回调
-fn emit_event(event_name: &str, data: &str) {
-    let callbacks = EVENT_CALLBACKS.lock().unwrap();
-    if let Some(callback) = callbacks.get(event_name) {
+fn emit_event(handle: Handle, data: &str) {
+    let event_name = match event_name(data) {
+        Some(event_name) => event_name,
+        None => return,
+    };
+    
+    // 先取出回调再调用，回调中可以再次注册而不会死锁
+    let callback = EVENT_CALLBACKS.lock().unwrap().get(&(handle, event_name)).cloned();
+    if let Some(callback) = callback {
         callback(data.to_string());
     }
 }
 
-// ===== 错误处理和重试 =====
+// 移除句柄的所有事件回调
+fn remove_event_callbacks(handle: Handle) {
+    EVENT_CALLBACKS.lock().unwrap().retain(|(callback_handle, _), _| *callback_handle != handle);
+}
+
+// ===== 句柄管理 =====
+
+// 最近一次失败的错误JSON
+//
+// 返回bool的接口无法携带错误信息，失败原因记录在调用线程上，由getLastError读取
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(error_json: String) {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(error_json));
+}
+
+// 读取并清除最近一次失败的错误JSON
+fn take_last_error() -> Option<String> {
+    LAST_ERROR.with(|last_error| last_error.borrow_mut().take())
+}
+
+// 生成句柄无效时返回给RN的错误JSON
+fn handle_error_json(error: &HandleError) -> String {
+    json!({
+        "error": {
+            "code": error.code(),
+            "message": error.to_string(),
+            "handle": error.handle()
+        }
+    }).to_string()
+}
+
+// 创建客户端并分配句柄
+fn create_client() -> Handle {
+    let client = Arc::new(McpClient::new());
+    let handle = HANDLES.write().unwrap().insert(HandleEntry::Client(client.clone()));
+    client.set_rn_callback(event_dispatcher(handle));
+    handle
+}
+
+// 创建多服务器管理器并分配句柄
+fn create_manager() -> Handle {
+    let manager = Arc::new(McpClientManager::new());
+    let handle = HANDLES.write().unwrap().insert(HandleEntry::Manager(manager.clone()));
+    // 管理器中各服务器的事件也通过句柄的回调发送
+    manager.set_rn_callback(event_dispatcher(handle));
+    handle
+}
+
+// 释放句柄并断开其连接，过期句柄或重复释放时返回false
+fn release_handle(handle: Handle, kind: HandleKind) -> bool {
+    let entry = HANDLES.write().unwrap().remove(handle, kind);
+    let entry = match entry {
+        Ok(entry) => entry,
+        Err(err) => {
+            set_last_error(handle_error_json(&err));
+            return false;
+        }
+    };
+    
+    remove_event_callbacks(handle);
+    
+    // 在运行时中断开连接，不阻塞调用方；其他线程上进行中的调用持有对象的引用，等它们结束后才会真正释放
+    match entry {
+        HandleEntry::Client(client) => {
+            RUNTIME.spawn(async move {
+                if let Err(e) = client.disconnect().await {
+                    log::warn!("释放句柄{}时断开连接失败: {}", handle, e);
+                }
+            });
+        }
+        HandleEntry::Manager(manager) => {
+            RUNTIME.spawn(async move { manager.disconnect_all().await });
+        }
+    }
+    true
+}
+
+// 查找客户端句柄，失败时返回错误JSON
+fn lookup_client(handle: Handle) -> Result<Arc<McpClient>, String> {
+    HANDLES.read().unwrap().client(handle).map_err(|err| handle_error_json(&err))
+}
+
+// 查找管理器句柄，失败时返回错误JSON
+fn lookup_manager(handle: Handle) -> Result<Arc<McpClientManager>, String> {
+    HANDLES.read().unwrap().manager(handle).map_err(|err| handle_error_json(&err))
+}
+
+// 在句柄指向的客户端上执行操作，句柄无效时返回错误JSON
+fn with_client<F>(handle: Handle, operation: F) -> String
+where
+    F: FnOnce(&McpClient) -> String,
+{
+    match lookup_client(handle) {
+        Ok(client) => operation(&client),
+        Err(error_json) => error_json,
+    }
+}
+
+// 在句柄指向的管理器上执行操作，句柄无效时返回错误JSON
+fn with_manager<F>(handle: Handle, operation: F) -> String
+where
+    F: FnOnce(&McpClientManager) -> String,
+{
+    match lookup_manager(handle) {
+        Ok(manager) => operation(&manager),
+        Err(error_json) => error_json,
+    }
+}
+
+// 记录返回bool的操作的结果，失败时把错误JSON记为最近错误
+fn record_status(result: Result<(), String>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(error_json) => {
+            set_last_error(error_json);
+            false
+        }
+    }
+}
+
+// 在句柄指向的客户端上执行返回bool的操作，操作返回的错误JSON会记为最近错误
+fn client_status<F>(handle: Handle, operation: F) -> bool
+where
+    F: FnOnce(&McpClient) -> Result<(), String>,
+{
+    record_status(lookup_client(handle).and_then(|client| operation(&client)))
+}
+
+// 耗时调用的完成回调，每次调用恰好回调一次
+//
+// 返回JSON的调用总是传入结果JSON；返回bool的调用成功时传入None，失败时传入错误JSON
+type Completion = Box<dyn FnOnce(Option<String>) + Send>;
+
+// 在运行时中执行客户端上的耗时调用，完成后通过回调返回结果JSON
+//
+// 调用期间只持有客户端的引用，不持有句柄注册表的锁，其他线程可以同时取消调用或回复服务器请求
+fn spawn_client_call<F, Fut>(handle: Handle, completion: Completion, call: F)
+where
+    F: FnOnce(Arc<McpClient>) -> Fut,
+    Fut: Future<Output = String> + Send + 'static,
+{
+    match lookup_client(handle) {
+        Ok(client) => {
+            let call = call(client);
+            RUNTIME.spawn(async move { completion(Some(call.await)) });
+        }
+        Err(error_json) => completion(Some(error_json)),
+    }
+}
+
+// 在运行时中执行客户端上返回bool的耗时调用，失败时通过回调返回错误JSON
+fn spawn_client_status<F, Fut>(handle: Handle, completion: Completion, call: F)
+where
+    F: FnOnce(Arc<McpClient>) -> Fut,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    match lookup_client(handle) {
+        Ok(client) => {
+            let call = call(client);
+            RUNTIME.spawn(async move { completion(call.await.err()) });
+        }
+        Err(error_json) => completion(Some(error_json)),
+    }
+}
 
-// 重试配置
-#[derive(Clone, Debug)]
-struct RetryConfig {
-    max_retries: usize,
-    initial_delay_ms: u64,
-    backoff_factor: f64,
-    max_delay_ms: u64,
-}
-
-impl Default for RetryConfig {
-    fn default() -> Self {
-        RetryConfig {
-            max_retries: 3,
-            initial_delay_ms: 500,
-            backoff_factor: 1.5,
-            max_delay_ms: 5000,
+// 在运行时中执行管理器上的耗时调用，完成后通过回调返回结果JSON
+fn spawn_manager_call<F, Fut>(handle: Handle, completion: Completion, call: F)
+where
+    F: FnOnce(Arc<McpClientManager>) -> Fut,
+    Fut: Future<Output = String> + Send + 'static,
+{
+    match lookup_manager(handle) {
+        Ok(manager) => {
+            let call = call(manager);
+            RUNTIME.spawn(async move { completion(Some(call.await)) });
         }
+        Err(error_json) => completion(Some(error_json)),
     }
 }
 
+// ===== 错误处理和重试 =====
+
 // 异步重试函数
 async fn retry_async<F, Fut, T, E>(
     operation: F,
@@ -90,16 +311,10 @@ where
                 last_error = Some(err);
                 
                 // 等待延迟时间
-                #[cfg(not(target_arch = "wasm32"))]
-                {
-                    std::thread::sleep(std::time::Duration::from_millis(delay_ms));
-                }
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
                 
                 // 增加延迟时间（指数退避）
-                delay_ms = std::cmp::min(
-                    (delay_ms as f64 * config.backoff_factor) as u64,
-                    config.max_delay_ms,
-                );
+                delay_ms = config.next_delay_ms(delay_ms);
             }
         }
     }
@@ -108,7 +323,15 @@ where
     Err(last_error.unwrap())
 }
 
-// 判断错误是否可重试
+// 判断请求错误是否可重试
+//
+// 工具调用不是幂等的，只重试请求未到达服务器的错误 (连接或写入失败)；
+// 超时、取消和服务器返回的错误说明请求可能已被执行，不能重发
+fn is_request_retryable(error: &McpError) -> bool {
+    matches!(error, McpError::ConnectionError(_))
+}
+
+// 判断连接错误是否可重试
 fn is_error_retryable(error: &McpError) -> bool {
     match error {
         McpError::ConnectionError(_) => true,
@@ -122,7 +345,350 @@ fn is_error_retryable(error: &McpError) -> bool {
     }
 }
 
+// 生成工具调用失败时返回给RN的错误JSON
+fn tool_call_error_json(error: &McpError) -> String {
+    match error {
+        McpError::InvalidArguments { violations, .. } => json!({
+            "error": {
+                "code": "invalid_arguments",
+                "message": error.to_string(),
+                "violations": violations
+            }
+        }).to_string(),
+        _ => json!({
+            "error": {
+                "code": "tool_call_error",
+                "message": error.to_string()
+            }
+        }).to_string(),
+    }
+}
+
+// 生成返回给RN的错误JSON
+fn error_json(code: &str, message: &str) -> String {
+    json!({
+        "error": {
+            "code": code,
+            "message": message
+        }
+    }).to_string()
+}
+
+// 将调用结果序列化为返回给RN的JSON，失败时使用给定的错误码
+fn result_json<T: Serialize>(result: Result<T, McpError>, error_code: &str) -> String {
+    match result {
+        Ok(value) => serde_json::to_string(&value)
+            .unwrap_or_else(|_| error_json("serialization_error", "无法序列化响应")),
+        Err(err) => error_json(error_code, &err.to_string()),
+    }
+}
+
+// 展开资源URI模板，variables为JSON对象字符串
+fn expand_template_json(template: &str, variables: &str) -> String {
+    let variables: HashMap<String, Value> = match serde_json::from_str(variables) {
+        Ok(variables) => variables,
+        Err(_) => return error_json("invalid_params", "无法解析模板变量"),
+    };
+    
+    match expand_uri_template(template, &variables) {
+        Ok(uri) => json!({ "uri": uri }).to_string(),
+        Err(err) => error_json("invalid_template", &err.to_string()),
+    }
+}
+
+// 获取提示词，arguments为字符串键值对的JSON对象
+async fn get_prompt_json(client: &McpClient, name: &str, arguments: &str) -> String {
+    let arguments: HashMap<String, String> = if arguments.trim().is_empty() {
+        HashMap::new()
+    } else {
+        match serde_json::from_str(arguments) {
+            Ok(arguments) => arguments,
+            Err(_) => return error_json("invalid_params", "无法解析提示词参数"),
+        }
+    };
+    
+    result_json(client.get_prompt(name, arguments).await, "get_prompt_error")
+}
+
+// 解析RN对服务器请求的回复: {"result": ...} 或 {"error": {"code", "message"}}
+fn parse_host_response(response_json: &str) -> Result<Value, McpError> {
+    let response: Value = match serde_json::from_str(response_json) {
+        Ok(response) => response,
+        Err(e) => return Err(McpError::InternalError(format!("无法解析回复: {}", e))),
+    };
+    
+    if let Some(error) = response.get("error") {
+        let code = match error.get("code") {
+            Some(Value::String(code)) => code.clone(),
+            Some(Value::Number(code)) => code.to_string(),
+            _ => "internal_error".to_string(),
+        };
+        let message = error.get("message").and_then(Value::as_str).unwrap_or("").to_string();
+        
+        return match code.as_str() {
+            "cancelled" | "user_rejected" => Err(McpError::Cancelled),
+            _ => Err(McpError::ServerError { code, message }),
+        };
+    }
+    
+    Ok(response.get("result").cloned().unwrap_or_else(|| json!({})))
+}
+
+// 更新根目录，roots为[{"uri", "name"}]形式的JSON数组
+async fn set_roots_json(client: &McpClient, roots: &str) -> String {
+    let roots: Vec<McpRoot> = match serde_json::from_str(roots) {
+        Ok(roots) => roots,
+        Err(_) => return error_json("invalid_params", "无法解析根目录列表"),
+    };
+    
+    let result = client.set_roots(roots).await.map(|_| client.roots());
+    result_json(result, "set_roots_error")
+}
+
+// 设置服务器日志级别，level为debug、info、warning等协议中的级别名
+async fn set_server_log_level_json(client: &McpClient, level: &str) -> String {
+    let level: LoggingLevel = match serde_json::from_value(json!(level)) {
+        Ok(level) => level,
+        Err(_) => return error_json("invalid_params", &format!("未知的日志级别: {}", level)),
+    };
+    
+    let result = client.set_server_log_level(level).await.map(|_| json!({ "level": level }));
+    result_json(result, "set_log_level_error")
+}
+
+// 设置服务器日志输出目标，sinks为[{"type": "rn_event"}, {"type": "ring_buffer", "capacity": 200}]形式的JSON数组
+fn set_log_sinks_json(client: &McpClient, sinks: &str) -> String {
+    match serde_json::from_str::<Vec<LogSink>>(sinks) {
+        Ok(sinks) => {
+            client.set_log_sinks(sinks.clone());
+            result_json(Ok(sinks), "set_log_sinks_error")
+        }
+        Err(_) => error_json("invalid_params", "无法解析日志输出目标"),
+    }
+}
+
+// 请求参数补全，reference为{"type": "ref/prompt", "name": ...}或{"type": "ref/resource", "uri": ...}
+async fn complete_json(client: &McpClient, reference: &str, argument_name: &str, partial_value: &str) -> String {
+    let reference: CompletionReference = match serde_json::from_str(reference) {
+        Ok(reference) => reference,
+        Err(_) => return error_json("invalid_params", "无法解析补全目标"),
+    };
+    
+    result_json(client.complete(reference, argument_name, partial_value).await, "completion_error")
+}
+
+// 回复elicitation请求，action为accept/decline/cancel，content为用户填写内容的JSON对象 (仅accept时需要)
+fn respond_to_elicitation_json(client: &McpClient, request_id: &str, action: &str, content: &str) -> String {
+    let action: ElicitAction = match serde_json::from_value(json!(action)) {
+        Ok(action) => action,
+        Err(_) => return error_json("invalid_params", &format!("未知的处理方式: {}", action)),
+    };
+    
+    let content = if content.trim().is_empty() {
+        None
+    } else {
+        match serde_json::from_str(content) {
+            Ok(content) => Some(content),
+            Err(_) => return error_json("invalid_params", "无法解析填写内容"),
+        }
+    };
+    
+    match client.respond_to_elicitation(request_id, ElicitResult { action, content }) {
+        Ok(true) => json!({ "requestId": request_id }).to_string(),
+        Ok(false) => error_json("request_not_found", "未找到对应的请求，可能已取消或连接已断开"),
+        Err(McpError::InvalidContent { violations }) => json!({
+            "error": {
+                "code": "invalid_content",
+                "message": McpError::InvalidContent { violations: violations.clone() }.to_string(),
+                "violations": violations
+            }
+        }).to_string(),
+        Err(err) => error_json("elicitation_error", &err.to_string()),
+    }
+}
+
+// 设置健康检查，interval_ms为0时关闭
+fn set_health_check(handle: Handle, interval_ms: u64, failure_threshold: u32) -> bool {
+    let config = if interval_ms == 0 {
+        None
+    } else {
+        Some(HealthCheckConfig {
+            interval: Duration::from_millis(interval_ms),
+            failure_threshold: failure_threshold.max(1),
+            ..HealthCheckConfig::default()
+        })
+    };
+    
+    client_status(handle, |client| {
+        // 已连接时需要在运行时中重启检查任务
+        let _guard = RUNTIME.enter();
+        client.set_health_check(config);
+        Ok(())
+    })
+}
+
+// 设置自动重连，max_retries为0时关闭；replay为true时重连后重发未完成的请求
+fn set_reconnect(handle: Handle, max_retries: u32, replay: bool) -> bool {
+    let config = if max_retries == 0 {
+        None
+    } else {
+        let mut config = ReconnectConfig::default();
+        config.retry.max_retries = max_retries as usize;
+        config.in_flight = if replay { InFlightPolicy::Replay } else { InFlightPolicy::Fail };
+        Some(config)
+    };
+    
+    client_status(handle, |client| {
+        client.set_reconnect(config);
+        Ok(())
+    })
+}
+
+// 连接管理器中的服务器，服务器ID已存在时替换原连接
+async fn connect_server_json(manager: &McpClientManager, server_id: &str, server_url: &str) -> String {
+    let result = manager.connect(server_id, server_url).await
+        .map(|_| json!({ "server_id": server_id, "connected": true }));
+    result_json(result, "connection_error")
+}
+
+// 断开并移除管理器中的服务器
+async fn remove_server_json(manager: &McpClientManager, server_id: &str) -> String {
+    let result = manager.remove_server(server_id).await
+        .map(|_| json!({ "server_id": server_id, "removed": true }));
+    result_json(result, "remove_server_error")
+}
+
+// 聚合所有服务器的工具 (工具名带服务器前缀)
+async fn list_all_tools_json(manager: &McpClientManager) -> String {
+    result_json(Ok(manager.list_tools().await), "list_tools_error")
+}
+
+// 调用工具并路由到提供该工具的服务器
+async fn call_server_tool_json(manager: &McpClientManager, tool_name: &str, parameters: &str) -> String {
+    let parameters: HashMap<String, Value> = match serde_json::from_str(parameters) {
+        Ok(parameters) => parameters,
+        Err(_) => return error_json("invalid_params", "无法解析工具参数"),
+    };
+    
+    match manager.call_tool(tool_name, parameters).await {
+        Ok(response) => result_json(Ok(response), "serialization_error"),
+        Err(err) => tool_call_error_json(&err),
+    }
+}
+
+// 各服务器的连接状态
+fn server_states_json(manager: &McpClientManager) -> String {
+    result_json(Ok(manager.server_states()), "server_state_error")
+}
+
+// 设置首选线路格式，format为json_rpc或tagged，在下次连接握手时生效
+fn set_wire_format(handle: Handle, format: &str) -> bool {
+    let format: WireFormat = match serde_json::from_value(json!(format)) {
+        Ok(format) => format,
+        Err(_) => {
+            set_last_error(error_json("invalid_params", &format!("未知的线路格式: {}", format)));
+            return false;
+        }
+    };
+    
+    client_status(handle, |client| {
+        client.set_wire_format(format);
+        Ok(())
+    })
+}
+
+// 设置按地址连接时的附加选项，options为{"headers": {...}, "sseEndpoint": ..., "args": [...], "env": {...}, "workingDir": ...}形式的JSON，在下次连接时生效
+fn set_connect_options(handle: Handle, options: &str) -> bool {
+    let options: ConnectOptions = match serde_json::from_str(options) {
+        Ok(options) => options,
+        Err(_) => {
+            set_last_error(error_json("invalid_params", "无法解析连接选项"));
+            return false;
+        }
+    };
+    
+    client_status(handle, |client| {
+        client.set_connect_options(options);
+        Ok(())
+    })
+}
+
+// 带重试地连接服务器，每次尝试之间不持有任何锁
+async fn connect_client(client: &McpClient, server_url: &str) -> Result<(), String> {
+    retry_async(
+        || client.connect_url(server_url),
+        RetryConfig::default(),
+        is_error_retryable,
+    ).await.map_err(|err| error_json("connection_error", &err.to_string()))
+}
+
+// 调用工具，parameters为JSON对象字符串
+//
+// 只重试请求未到达服务器的错误，调用进行中可以通过cancel取消
+async fn call_tool_json(client: &McpClient, tool_name: &str, parameters: &str) -> String {
+    let parameters: HashMap<String, Value> = match serde_json::from_str(parameters) {
+        Ok(parameters) => parameters,
+        Err(_) => return error_json("invalid_params", "无法解析工具参数"),
+    };
+    
+    let result = retry_async(
+        || client.call_tool(tool_name, parameters.clone()),
+        RetryConfig::default(),
+        is_request_retryable,
+    ).await;
+    
+    match result {
+        Ok(response) => result_json(Ok(response), "serialization_error"),
+        Err(err) => tool_call_error_json(&err),
+    }
+}
+
+// 请求资源，二进制资源通过blob字段返回Base64字符串，RN端直接解码即可
+async fn request_resource_json(client: &McpClient, uri: &str) -> String {
+    let result = retry_async(
+        || client.request_resource(uri),
+        RetryConfig::default(),
+        is_request_retryable,
+    ).await;
+    result_json(result, "resource_request_error")
+}
+
+// 取消进行中的调用，未找到对应调用时返回错误JSON
+fn cancel_call(client: &McpClient, call_id: &str) -> Result<(), String> {
+    match RUNTIME.block_on(client.cancel(call_id, None)) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(error_json("request_not_found", "未找到对应的调用，可能已完成")),
+        Err(err) => Err(error_json("cancel_error", &err.to_string())),
+    }
+}
+
+// 将RN的回复转交给等待中的服务器请求
+fn respond_to_server_request(client: &McpClient, request_id: &str, response_json: &str) -> Result<(), String> {
+    if client.respond_to_server_request(request_id, parse_host_response(response_json)) {
+        Ok(())
+    } else {
+        Err(error_json("request_not_found", "未找到对应的请求，可能已取消或连接已断开"))
+    }
+}
+
+// 处理来自RN的输入
+fn handle_input(client: &McpClient, message: &str) -> Result<(), String> {
+    // 服务器请求的回复在运行时中异步发送
+    let _guard = RUNTIME.enter();
+    client.handle_input_from_rn(message).map_err(|err| error_json("invalid_message", &err.to_string()))
+}
+
+// 服务器信息，尚未完成握手时为null
+fn server_info_json(client: &McpClient) -> String {
+    match client.get_server_info() {
+        Some(info) => result_json(Ok(info), "serialization_error"),
+        None => "null".to_string(),
+    }
+}
+
 // ===== Android 平台接口 =====
+//
+// 除initClient/initManager和纯函数外，每个入口都以句柄为第一个参数
 
 #[cfg(target_os = "android")]
 #[no_mangle]
@@ -130,12 +696,30 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_initClient(
     _env: JNIEnv,
     _class: JClass,
 ) -> jlong {
-    let client = McpClient::new();
-    let mut global_client = MCP_CLIENT.lock().unwrap();
-    *global_client = Some(client);
-    
-    // 返回一个非零值表示成功
-    1
+    // 句柄从1开始分配，0不是有效句柄
+    create_client() as jlong
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_destroyClient(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    release_handle(handle as Handle, HandleKind::Client) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_getLastError(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    match take_last_error() {
+        Some(error_json) => env.new_string(error_json).unwrap().into_inner(),
+        None => JObject::null().into_inner(),
+    }
 }
 
 #[cfg(target_os = "android")]
@@ -143,47 +727,28 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_initClient(
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_connect(
     env: JNIEnv,
     _class: JClass,
+    handle: jlong,
     server_url: JString,
-) -> jboolean {
+    callback: JObject,
+) {
     let server_url: String = env.get_string(server_url).unwrap().into();
     
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        // 使用异步运行时阻塞执行
-        let result = futures::executor::block_on(async {
-            let retry_config = RetryConfig::default();
-            retry_async(
-                || async { client.connect(&server_url).await },
-                retry_config,
-                is_error_retryable
-            ).await
-        });
-        
-        match result {
-            Ok(_) => 1, // true
-            Err(_) => 0, // false
-        }
-    } else {
-        0 // false
-    }
+    spawn_client_status(handle as Handle, java_completion(&env, callback), move |client| async move {
+        connect_client(&client, &server_url).await
+    });
 }
 
 #[cfg(target_os = "android")]
 #[no_mangle]
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_disconnect(
-    _env: JNIEnv,
+    env: JNIEnv,
     _class: JClass,
-) -> jboolean {
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        let result = futures::executor::block_on(client.disconnect());
-        match result {
-            Ok(_) => 1, // true
-            Err(_) => 0, // false
-        }
-    } else {
-        1 // 已断开连接视为成功
-    }
+    handle: jlong,
+    callback: JObject,
+) {
+    spawn_client_status(handle as Handle, java_completion(&env, callback), move |client| async move {
+        client.disconnect().await.map_err(|err| error_json("disconnect_error", &err.to_string()))
+    });
 }
 
 #[cfg(target_os = "android")]
@@ -191,13 +756,11 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_disconnect(
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_isConnected(
     _env: JNIEnv,
     _class: JClass,
+    handle: jlong,
 ) -> jboolean {
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        if client.is_connected() { 1 } else { 0 }
-    } else {
-        0 // false
-    }
+    client_status(handle as Handle, |client| {
+        if client.is_connected() { Ok(()) } else { Err(error_json("not_connected", "未连接到服务器")) }
+    }) as jboolean
 }
 
 #[cfg(target_os = "android")]
@@ -205,71 +768,17 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_isConnected(
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_callTool(
     env: JNIEnv,
     _class: JClass,
+    handle: jlong,
     tool_name: JString,
     parameters_json: JString,
-) -> jstring {
+    callback: JObject,
+) {
     let tool_name: String = env.get_string(tool_name).unwrap().into();
-    let parameters_str: String = env.get_string(parameters_json).unwrap().into();
+    let parameters_json: String = env.get_string(parameters_json).unwrap().into();
     
-    // 解析参数
-    let parameters: HashMap<String, Value> = match serde_json::from_str(&parameters_str) {
-        Ok(params) => params,
-        Err(_) => {
-            let error_json = json!({
-                "error": {
-                    "code": "invalid_params",
-                    "message": "无法解析工具参数"
-                }
-            }).to_string();
-            return env.new_string(error_json).unwrap().into_inner();
-        }
-    };
-    
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        // 调用工具并处理结果
-        let result = futures::executor::block_on(async {
-            let retry_config = RetryConfig::default();
-            retry_async(
-                || async { client.call_tool(&tool_name, parameters.clone()).await },
-                retry_config,
-                is_error_retryable
-            ).await
-        });
-        
-        let response_json = match result {
-            Ok(response) => {
-                match serde_json::to_string(&response) {
-                    Ok(json) => json,
-                    Err(_) => json!({
-                        "error": {
-                            "code": "serialization_error",
-                            "message": "无法序列化响应"
-                        }
-                    }).to_string(),
-                }
-            },
-            Err(err) => {
-                json!({
-                    "error": {
-                        "code": "tool_call_error",
-                        "message": err.to_string()
-                    }
-                }).to_string()
-            }
-        };
-        
-        env.new_string(response_json).unwrap().into_inner()
-    } else {
-        let error_json = json!({
-            "error": {
-                "code": "client_not_initialized",
-                "message": "MCP客户端未初始化"
-            }
-        }).to_string();
-        
-        env.new_string(error_json).unwrap().into_inner()
-    }
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        call_tool_json(&client, &tool_name, &parameters_json).await
+    });
 }
 
 #[cfg(target_os = "android")]
@@ -277,55 +786,373 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_callTool(
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_requestResource(
     env: JNIEnv,
     _class: JClass,
+    handle: jlong,
     uri: JString,
-) -> jstring {
+    callback: JObject,
+) {
     let uri: String = env.get_string(uri).unwrap().into();
     
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        // 请求资源并处理结果
-        let result = futures::executor::block_on(async {
-            let retry_config = RetryConfig::default();
-            retry_async(
-                || async { client.request_resource(&uri).await },
-                retry_config,
-                is_error_retryable
-            ).await
-        });
-        
-        let response_json = match result {
-            Ok(resource) => {
-                match serde_json::to_string(&resource) {
-                    Ok(json) => json,
-                    Err(_) => json!({
-                        "error": {
-                            "code": "serialization_error",
-                            "message": "无法序列化资源"
-                        }
-                    }).to_string(),
-                }
-            },
-            Err(err) => {
-                json!({
-                    "error": {
-                        "code": "resource_request_error",
-                        "message": err.to_string()
-                    }
-                }).to_string()
-            }
-        };
-        
-        env.new_string(response_json).unwrap().into_inner()
-    } else {
-        let error_json = json!({
-            "error": {
-                "code": "client_not_initialized",
-                "message": "MCP客户端未初始化"
-            }
-        }).to_string();
-        
-        env.new_string(error_json).unwrap().into_inner()
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        request_resource_json(&client, &uri).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_cancel(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    call_id: JString,
+) -> jboolean {
+    let call_id: String = env.get_string(call_id).unwrap().into();
+    
+    // 进行中的调用在运行时中等待响应，取消可以随时执行
+    client_status(handle as Handle, |client| cancel_call(client, &call_id)) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setRequestTimeout(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    timeout_ms: jlong,
+) -> jboolean {
+    if timeout_ms <= 0 {
+        set_last_error(error_json("invalid_params", "超时时间必须大于0"));
+        return 0; // false
+    }
+    
+    client_status(handle as Handle, |client| {
+        client.set_request_timeout(Duration::from_millis(timeout_ms as u64));
+        Ok(())
+    }) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setHealthCheck(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    interval_ms: jlong,
+    failure_threshold: jint,
+) -> jboolean {
+    if interval_ms < 0 || failure_threshold < 0 {
+        set_last_error(error_json("invalid_params", "检查间隔和失败阈值不能为负数"));
+        return 0; // false
+    }
+    
+    set_health_check(handle as Handle, interval_ms as u64, failure_threshold as u32) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setReconnect(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    max_retries: jint,
+    replay: jboolean,
+) -> jboolean {
+    if max_retries < 0 {
+        set_last_error(error_json("invalid_params", "重试次数不能为负数"));
+        return 0; // false
     }
+    
+    set_reconnect(handle as Handle, max_retries as u32, replay != 0) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setWireFormat(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    format: JString,
+) -> jboolean {
+    let format: String = env.get_string(format).unwrap().into();
+    
+    set_wire_format(handle as Handle, &format) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setConnectOptions(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    options_json: JString,
+) -> jboolean {
+    let options_json: String = env.get_string(options_json).unwrap().into();
+    
+    set_connect_options(handle as Handle, &options_json) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_getConnectionHealth(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let response_json = with_client(handle as Handle, |client| {
+        result_json(Ok(client.connection_health()), "health_error")
+    });
+    env.new_string(response_json).unwrap().into_inner()
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_listTools(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    callback: JObject,
+) {
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        result_json(client.list_tools().await, "list_tools_error")
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_listResources(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    callback: JObject,
+) {
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        result_json(client.list_resources().await, "list_resources_error")
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_listResourceTemplates(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    callback: JObject,
+) {
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        result_json(client.list_resource_templates().await, "list_resource_templates_error")
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_expandResourceTemplate(
+    env: JNIEnv,
+    _class: JClass,
+    template: JString,
+    variables_json: JString,
+) -> jstring {
+    let template: String = env.get_string(template).unwrap().into();
+    let variables_json: String = env.get_string(variables_json).unwrap().into();
+    
+    let response_json = expand_template_json(&template, &variables_json);
+    env.new_string(response_json).unwrap().into_inner()
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_subscribeResource(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    uri: JString,
+    callback: JObject,
+) {
+    let uri: String = env.get_string(uri).unwrap().into();
+    
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        let result = client.subscribe_resource(&uri).await.map(|_| json!({ "uri": uri }));
+        result_json(result, "subscribe_error")
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_unsubscribeResource(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    uri: JString,
+    callback: JObject,
+) {
+    let uri: String = env.get_string(uri).unwrap().into();
+    
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        let result = client.unsubscribe_resource(&uri).await.map(|_| json!({ "uri": uri }));
+        result_json(result, "unsubscribe_error")
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_listPrompts(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    callback: JObject,
+) {
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        result_json(client.list_prompts().await, "list_prompts_error")
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_getPrompt(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    name: JString,
+    arguments_json: JString,
+    callback: JObject,
+) {
+    let name: String = env.get_string(name).unwrap().into();
+    let arguments_json: String = env.get_string(arguments_json).unwrap().into();
+    
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        get_prompt_json(&client, &name, &arguments_json).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_complete(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    reference_json: JString,
+    argument_name: JString,
+    partial_value: JString,
+    callback: JObject,
+) {
+    let reference_json: String = env.get_string(reference_json).unwrap().into();
+    let argument_name: String = env.get_string(argument_name).unwrap().into();
+    let partial_value: String = env.get_string(partial_value).unwrap().into();
+    
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        complete_json(&client, &reference_json, &argument_name, &partial_value).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_forwardServerRequests(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    method: JString,
+) -> jboolean {
+    let method: String = env.get_string(method).unwrap().into();
+    
+    // 需在连接前调用，以便握手时声明对应能力
+    client_status(handle as Handle, |client| {
+        client.forward_request_to_rn(&method);
+        Ok(())
+    }) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_respondToServerRequest(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_id: JString,
+    response_json: JString,
+) -> jboolean {
+    let request_id: String = env.get_string(request_id).unwrap().into();
+    let response_json: String = env.get_string(response_json).unwrap().into();
+    
+    client_status(handle as Handle, |client| {
+        respond_to_server_request(client, &request_id, &response_json)
+    }) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_respondToElicitation(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    request_id: JString,
+    action: JString,
+    content_json: JString,
+) -> jstring {
+    let request_id: String = env.get_string(request_id).unwrap().into();
+    let action: String = env.get_string(action).unwrap().into();
+    let content_json: String = env.get_string(content_json).unwrap().into();
+    
+    let response_json = with_client(handle as Handle, |client| {
+        respond_to_elicitation_json(client, &request_id, &action, &content_json)
+    });
+    env.new_string(response_json).unwrap().into_inner()
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setRoots(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    roots_json: JString,
+    callback: JObject,
+) {
+    let roots_json: String = env.get_string(roots_json).unwrap().into();
+    
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        set_roots_json(&client, &roots_json).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setServerLogLevel(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    level: JString,
+    callback: JObject,
+) {
+    let level: String = env.get_string(level).unwrap().into();
+    
+    spawn_client_call(handle as Handle, java_completion(&env, callback), move |client| async move {
+        set_server_log_level_json(&client, &level).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_setLogSinks(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sinks_json: JString,
+) -> jstring {
+    let sinks_json: String = env.get_string(sinks_json).unwrap().into();
+    
+    let response_json = with_client(handle as Handle, |client| set_log_sinks_json(client, &sinks_json));
+    env.new_string(response_json).unwrap().into_inner()
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_getServerLogs(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let response_json = with_client(handle as Handle, |client| {
+        result_json(Ok(client.recent_server_logs()), "get_logs_error")
+    });
+    env.new_string(response_json).unwrap().into_inner()
 }
 
 #[cfg(target_os = "android")]
@@ -333,37 +1160,10 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_requestResource(
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_getServerInfo(
     env: JNIEnv,
     _class: JClass,
+    handle: jlong,
 ) -> jstring {
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        let server_info = client.get_server_info();
-        
-        let response_json = match server_info {
-            Some(info) => {
-                match serde_json::to_string(&info) {
-                    Ok(json) => json,
-                    Err(_) => json!({
-                        "error": {
-                            "code": "serialization_error",
-                            "message": "无法序列化服务器信息"
-                        }
-                    }).to_string(),
-                }
-            },
-            None => "null".to_string(),
-        };
-        
-        env.new_string(response_json).unwrap().into_inner()
-    } else {
-        let error_json = json!({
-            "error": {
-                "code": "client_not_initialized",
-                "message": "MCP客户端未初始化"
-            }
-        }).to_string();
-        
-        env.new_string(error_json).unwrap().into_inner()
-    }
+    let response_json = with_client(handle as Handle, server_info_json);
+    env.new_string(response_json).unwrap().into_inner()
 }
 
 #[cfg(target_os = "android")]
@@ -371,19 +1171,129 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_getServerInfo(
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_handleInputFromRN(
     env: JNIEnv,
     _class: JClass,
+    handle: jlong,
     message: JString,
 ) -> jboolean {
     let message: String = env.get_string(message).unwrap().into();
     
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        match client.handle_input_from_rn(&message) {
-            Ok(_) => 1, // true
-            Err(_) => 0, // false
-        }
-    } else {
-        0 // false
-    }
+    client_status(handle as Handle, |client| handle_input(client, &message)) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_initManager(
+    _env: JNIEnv,
+    _class: JClass,
+) -> jlong {
+    create_manager() as jlong
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_destroyManager(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    release_handle(handle as Handle, HandleKind::Manager) as jboolean
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_connectServer(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    server_id: JString,
+    server_url: JString,
+    callback: JObject,
+) {
+    let server_id: String = env.get_string(server_id).unwrap().into();
+    let server_url: String = env.get_string(server_url).unwrap().into();
+    
+    spawn_manager_call(handle as Handle, java_completion(&env, callback), move |manager| async move {
+        connect_server_json(&manager, &server_id, &server_url).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_removeServer(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    server_id: JString,
+    callback: JObject,
+) {
+    let server_id: String = env.get_string(server_id).unwrap().into();
+    
+    spawn_manager_call(handle as Handle, java_completion(&env, callback), move |manager| async move {
+        remove_server_json(&manager, &server_id).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_listAllTools(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    callback: JObject,
+) {
+    spawn_manager_call(handle as Handle, java_completion(&env, callback), move |manager| async move {
+        list_all_tools_json(&manager).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_callServerTool(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    tool_name: JString,
+    parameters_json: JString,
+    callback: JObject,
+) {
+    let tool_name: String = env.get_string(tool_name).unwrap().into();
+    let parameters_json: String = env.get_string(parameters_json).unwrap().into();
+    
+    spawn_manager_call(handle as Handle, java_completion(&env, callback), move |manager| async move {
+        call_server_tool_json(&manager, &tool_name, &parameters_json).await
+    });
+}
+
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "system" fn Java_com_your_app_RustMcpClientModule_getServerStates(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let response_json = with_manager(handle as Handle, server_states_json);
+    env.new_string(response_json).unwrap().into_inner()
+}
+
+// 将Java端的ResultCallback包装为完成回调，回调在运行时的工作线程上调用
+#[cfg(target_os = "android")]
+fn java_completion(env: &JNIEnv, callback_obj: JObject) -> Completion {
+    let callback_ref = env.new_global_ref(callback_obj).unwrap();
+    let jvm = env.get_java_vm().unwrap();
+    
+    Box::new(move |result: Option<String>| {
+        let env = jvm.attach_current_thread().unwrap();
+        let result = match result {
+            Some(result) => JObject::from(env.new_string(result).unwrap()),
+            None => JObject::null(),
+        };
+        
+        let _result = env.call_method(
+            callback_ref.as_obj(),
+            "invoke",
+            "(Ljava/lang/String;)V",
+            &[JValue::Object(result)]
+        );
+    })
 }
 
 #[cfg(target_os = "android")]
@@ -391,15 +1301,15 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_handleInputFromRN(
 pub extern "system" fn Java_com_your_app_RustMcpClientModule_registerEventCallback(
     env: JNIEnv,
     _class: JClass,
+    handle: jlong,
     event_name: JString,
     callback_obj: JObject,
 ) -> jboolean {
     let event_name: String = env.get_string(event_name).unwrap().into();
-    
     let callback_ref = env.new_global_ref(callback_obj).unwrap();
     let jvm = env.get_java_vm().unwrap();
     
-    let callback = Box::new(move |data: String| {
+    let callback = Arc::new(move |data: String| {
         let env = jvm.attach_current_thread().unwrap();
         let data_jstring = env.new_string(data).unwrap();
         
@@ -408,303 +1318,534 @@ pub extern "system" fn Java_com_your_app_RustMcpClientModule_registerEventCallba
             callback_obj,
             "invoke",
             "(Ljava/lang/String;)V",
-            &[(&data_jstring).into()]
+            &[JValue::Object(JObject::from(data_jstring))]
         );
     });
     
-    register_event_callback(&event_name, callback);
-    
-    // 为MCP客户端设置回调
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        let callback_id = format!("rn_callback_{}", event_name);
-        client.set_rn_callback(RNCallback {
-            callback_id: callback_id.clone(),
-            handler: Arc::new(move |json| {
-                emit_event(&event_name, &json);
-            }),
-        });
+    // 客户端句柄和管理器句柄都可以注册
+    register_event_callback(handle as Handle, &event_name, callback) as jboolean
+}
+
+// ===== iOS 平台接口 =====
+//
+// 除mcp_init_client/mcp_init_manager和纯函数外，每个入口都以句柄为第一个参数
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_init_client() -> Handle {
+    // 句柄从1开始分配，0不是有效句柄
+    create_client()
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_destroy_client(handle: Handle) -> bool {
+    release_handle(handle, HandleKind::Client)
+}
+
+// 读取并清除当前线程最近一次失败的错误JSON，没有错误时返回空指针
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_get_last_error() -> *mut c_char {
+    match take_last_error() {
+        Some(error_json) => CString::new(error_json).unwrap().into_raw(),
+        None => std::ptr::null_mut(),
     }
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_connect(
+    handle: Handle,
+    server_url: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let server_url = unsafe { CStr::from_ptr(server_url) }.to_str().unwrap().to_string();
     
-    1 // true
+    spawn_client_status(handle, c_completion(completion, context), move |client| async move {
+        connect_client(&client, &server_url).await
+    });
 }
 
-// ===== iOS 平台接口 =====
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_disconnect(
+    handle: Handle,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    spawn_client_status(handle, c_completion(completion, context), move |client| async move {
+        client.disconnect().await.map_err(|err| error_json("disconnect_error", &err.to_string()))
+    });
+}
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_init_client() -> bool {
-    let client = McpClient::new();
-    let mut global_client = MCP_CLIENT.lock().unwrap();
-    *global_client = Some(client);
-    true
+pub extern "C" fn mcp_is_connected(handle: Handle) -> bool {
+    client_status(handle, |client| {
+        if client.is_connected() { Ok(()) } else { Err(error_json("not_connected", "未连接到服务器")) }
+    })
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_call_tool(
+    handle: Handle,
+    tool_name: *const c_char,
+    parameters_json: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let tool_name = unsafe { CStr::from_ptr(tool_name) }.to_str().unwrap().to_string();
+    let parameters_json = unsafe { CStr::from_ptr(parameters_json) }.to_str().unwrap().to_string();
+    
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        call_tool_json(&client, &tool_name, &parameters_json).await
+    });
 }
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_connect(server_url: *const c_char) -> bool {
-    let c_str = unsafe { CStr::from_ptr(server_url) };
-    let server_url = c_str.to_str().unwrap().to_string();
+pub extern "C" fn mcp_request_resource(
+    handle: Handle,
+    uri: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let uri = unsafe { CStr::from_ptr(uri) }.to_str().unwrap().to_string();
     
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        // 使用异步运行时阻塞执行
-        let result = futures::executor::block_on(async {
-            let retry_config = RetryConfig::default();
-            retry_async(
-                || async { client.connect(&server_url).await },
-                retry_config,
-                is_error_retryable
-            ).await
-        });
-        
-        match result {
-            Ok(_) => true,
-            Err(_) => false,
-        }
-    } else {
-        false
-    }
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        request_resource_json(&client, &uri).await
+    });
 }
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_disconnect() -> bool {
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        let result = futures::executor::block_on(client.disconnect());
-        match result {
-            Ok(_) => true,
-            Err(_) => false,
-        }
-    } else {
-        true // 已断开连接视为成功
-    }
+pub extern "C" fn mcp_cancel(handle: Handle, call_id: *const c_char) -> bool {
+    let call_id_cstr = unsafe { CStr::from_ptr(call_id) };
+    let call_id = call_id_cstr.to_str().unwrap().to_string();
+    
+    // 进行中的调用在运行时中等待响应，取消可以随时执行
+    client_status(handle, |client| cancel_call(client, &call_id))
 }
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_is_connected() -> bool {
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        client.is_connected()
-    } else {
-        false
+pub extern "C" fn mcp_set_request_timeout(handle: Handle, timeout_ms: u64) -> bool {
+    if timeout_ms == 0 {
+        set_last_error(error_json("invalid_params", "超时时间必须大于0"));
+        return false;
     }
+    
+    client_status(handle, |client| {
+        client.set_request_timeout(Duration::from_millis(timeout_ms));
+        Ok(())
+    })
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_set_health_check(handle: Handle, interval_ms: u64, failure_threshold: u32) -> bool {
+    set_health_check(handle, interval_ms, failure_threshold)
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_set_reconnect(handle: Handle, max_retries: u32, replay: bool) -> bool {
+    set_reconnect(handle, max_retries, replay)
 }
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_call_tool(tool_name: *const c_char, parameters_json: *const c_char) -> *mut c_char {
-    let tool_name_cstr = unsafe { CStr::from_ptr(tool_name) };
-    let parameters_cstr = unsafe { CStr::from_ptr(parameters_json) };
+pub extern "C" fn mcp_set_wire_format(handle: Handle, format: *const c_char) -> bool {
+    let format = unsafe { CStr::from_ptr(format) }.to_str().unwrap().to_string();
     
-    let tool_name = tool_name_cstr.to_str().unwrap().to_string();
-    let parameters_str = parameters_cstr.to_str().unwrap().to_string();
+    set_wire_format(handle, &format)
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_set_connect_options(handle: Handle, options_json: *const c_char) -> bool {
+    let options_json = unsafe { CStr::from_ptr(options_json) }.to_str().unwrap().to_string();
     
-    // 解析参数
-    let parameters: HashMap<String, Value> = match serde_json::from_str(&parameters_str) {
-        Ok(params) => params,
-        Err(_) => {
-            let error_json = json!({
-                "error": {
-                    "code": "invalid_params",
-                    "message": "无法解析工具参数"
-                }
-            }).to_string();
-            
-            return CString::new(error_json).unwrap().into_raw();
-        }
-    };
+    set_connect_options(handle, &options_json)
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_get_connection_health(handle: Handle) -> *mut c_char {
+    let response_json = with_client(handle, |client| result_json(Ok(client.connection_health()), "health_error"));
+    CString::new(response_json).unwrap().into_raw()
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_list_tools(
+    handle: Handle,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        result_json(client.list_tools().await, "list_tools_error")
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_list_resources(
+    handle: Handle,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        result_json(client.list_resources().await, "list_resources_error")
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_list_resource_templates(
+    handle: Handle,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        result_json(client.list_resource_templates().await, "list_resource_templates_error")
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_expand_resource_template(template: *const c_char, variables_json: *const c_char) -> *mut c_char {
+    let template = unsafe { CStr::from_ptr(template) }.to_str().unwrap().to_string();
+    let variables_json = unsafe { CStr::from_ptr(variables_json) }.to_str().unwrap().to_string();
     
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        // 调用工具并处理结果
-        let result = futures::executor::block_on(async {
-            let retry_config = RetryConfig::default();
-            retry_async(
-                || async { client.call_tool(&tool_name, parameters.clone()).await },
-                retry_config,
-                is_error_retryable
-            ).await
-        });
-        
-        let response_json = match result {
-            Ok(response) => {
-                match serde_json::to_string(&response) {
-                    Ok(json) => json,
-                    Err(_) => json!({
-                        "error": {
-                            "code": "serialization_error",
-                            "message": "无法序列化响应"
-                        }
-                    }).to_string(),
-                }
-            },
-            Err(err) => {
-                json!({
-                    "error": {
-                        "code": "tool_call_error",
-                        "message": err.to_string()
-                    }
-                }).to_string()
-            }
-        };
-        
-        CString::new(response_json).unwrap().into_raw()
-    } else {
-        let error_json = json!({
-            "error": {
-                "code": "client_not_initialized",
-                "message": "MCP客户端未初始化"
-            }
-        }).to_string();
-        
-        CString::new(error_json).unwrap().into_raw()
-    }
+    let response_json = expand_template_json(&template, &variables_json);
+    CString::new(response_json).unwrap().into_raw()
 }
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_request_resource(uri: *const c_char) -> *mut c_char {
-    let uri_cstr = unsafe { CStr::from_ptr(uri) };
-    let uri = uri_cstr.to_str().unwrap().to_string();
+pub extern "C" fn mcp_subscribe_resource(
+    handle: Handle,
+    uri: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let uri = unsafe { CStr::from_ptr(uri) }.to_str().unwrap().to_string();
     
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        // 请求资源并处理结果
-        let result = futures::executor::block_on(async {
-            let retry_config = RetryConfig::default();
-            retry_async(
-                || async { client.request_resource(&uri).await },
-                retry_config,
-                is_error_retryable
-            ).await
-        });
-        
-        let response_json = match result {
-            Ok(resource) => {
-                match serde_json::to_string(&resource) {
-                    Ok(json) => json,
-                    Err(_) => json!({
-                        "error": {
-                            "code": "serialization_error",
-                            "message": "无法序列化资源"
-                        }
-                    }).to_string(),
-                }
-            },
-            Err(err) => {
-                json!({
-                    "error": {
-                        "code": "resource_request_error",
-                        "message": err.to_string()
-                    }
-                }).to_string()
-            }
-        };
-        
-        CString::new(response_json).unwrap().into_raw()
-    } else {
-        let error_json = json!({
-            "error": {
-                "code": "client_not_initialized",
-                "message": "MCP客户端未初始化"
-            }
-        }).to_string();
-        
-        CString::new(error_json).unwrap().into_raw()
-    }
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        let result = client.subscribe_resource(&uri).await.map(|_| json!({ "uri": uri }));
+        result_json(result, "subscribe_error")
+    });
 }
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_get_server_info() -> *mut c_char {
-    let client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_ref() {
-        let server_info = client.get_server_info();
-        
-        let response_json = match server_info {
-            Some(info) => {
-                match serde_json::to_string(&info) {
-                    Ok(json) => json,
-                    Err(_) => json!({
-                        "error": {
-                            "code": "serialization_error",
-                            "message": "无法序列化服务器信息"
-                        }
-                    }).to_string(),
-                }
-            },
-            None => "null".to_string(),
-        };
-        
-        CString::new(response_json).unwrap().into_raw()
-    } else {
-        let error_json = json!({
-            "error": {
-                "code": "client_not_initialized",
-                "message": "MCP客户端未初始化"
-            }
-        }).to_string();
-        
-        CString::new(error_json).unwrap().into_raw()
-    }
+pub extern "C" fn mcp_unsubscribe_resource(
+    handle: Handle,
+    uri: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let uri = unsafe { CStr::from_ptr(uri) }.to_str().unwrap().to_string();
+    
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        let result = client.unsubscribe_resource(&uri).await.map(|_| json!({ "uri": uri }));
+        result_json(result, "unsubscribe_error")
+    });
 }
 
 #[cfg(target_os = "ios")]
 #[no_mangle]
-pub extern "C" fn mcp_handle_input_from_rn(message: *const c_char) -> bool {
+pub extern "C" fn mcp_list_prompts(
+    handle: Handle,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        result_json(client.list_prompts().await, "list_prompts_error")
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_get_prompt(
+    handle: Handle,
+    name: *const c_char,
+    arguments_json: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let name = unsafe { CStr::from_ptr(name) }.to_str().unwrap().to_string();
+    let arguments_json = unsafe { CStr::from_ptr(arguments_json) }.to_str().unwrap().to_string();
+    
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        get_prompt_json(&client, &name, &arguments_json).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_complete(
+    handle: Handle,
+    reference_json: *const c_char,
+    argument_name: *const c_char,
+    partial_value: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let reference_json = unsafe { CStr::from_ptr(reference_json) }.to_str().unwrap().to_string();
+    let argument_name = unsafe { CStr::from_ptr(argument_name) }.to_str().unwrap().to_string();
+    let partial_value = unsafe { CStr::from_ptr(partial_value) }.to_str().unwrap().to_string();
+    
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        complete_json(&client, &reference_json, &argument_name, &partial_value).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_forward_server_requests(handle: Handle, method: *const c_char) -> bool {
+    let method = unsafe { CStr::from_ptr(method) }.to_str().unwrap().to_string();
+    
+    // 需在连接前调用，以便握手时声明对应能力
+    client_status(handle, |client| {
+        client.forward_request_to_rn(&method);
+        Ok(())
+    })
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_respond_to_server_request(
+    handle: Handle,
+    request_id: *const c_char,
+    response_json: *const c_char,
+) -> bool {
+    let request_id = unsafe { CStr::from_ptr(request_id) }.to_str().unwrap().to_string();
+    let response_json = unsafe { CStr::from_ptr(response_json) }.to_str().unwrap().to_string();
+    
+    client_status(handle, |client| respond_to_server_request(client, &request_id, &response_json))
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_respond_to_elicitation(
+    handle: Handle,
+    request_id: *const c_char,
+    action: *const c_char,
+    content_json: *const c_char,
+) -> *mut c_char {
+    let request_id = unsafe { CStr::from_ptr(request_id) }.to_str().unwrap().to_string();
+    let action = unsafe { CStr::from_ptr(action) }.to_str().unwrap().to_string();
+    let content_json = unsafe { CStr::from_ptr(content_json) }.to_str().unwrap().to_string();
+    
+    let response_json = with_client(handle, |client| {
+        respond_to_elicitation_json(client, &request_id, &action, &content_json)
+    });
+    CString::new(response_json).unwrap().into_raw()
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_set_roots(
+    handle: Handle,
+    roots_json: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let roots_json = unsafe { CStr::from_ptr(roots_json) }.to_str().unwrap().to_string();
+    
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        set_roots_json(&client, &roots_json).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_set_server_log_level(
+    handle: Handle,
+    level: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let level = unsafe { CStr::from_ptr(level) }.to_str().unwrap().to_string();
+    
+    spawn_client_call(handle, c_completion(completion, context), move |client| async move {
+        set_server_log_level_json(&client, &level).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_set_log_sinks(handle: Handle, sinks_json: *const c_char) -> *mut c_char {
+    let sinks_json = unsafe { CStr::from_ptr(sinks_json) }.to_str().unwrap().to_string();
+    
+    let response_json = with_client(handle, |client| set_log_sinks_json(client, &sinks_json));
+    CString::new(response_json).unwrap().into_raw()
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_get_server_logs(handle: Handle) -> *mut c_char {
+    let response_json = with_client(handle, |client| result_json(Ok(client.recent_server_logs()), "get_logs_error"));
+    CString::new(response_json).unwrap().into_raw()
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_get_server_info(handle: Handle) -> *mut c_char {
+    CString::new(with_client(handle, server_info_json)).unwrap().into_raw()
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_handle_input_from_rn(handle: Handle, message: *const c_char) -> bool {
     let message_cstr = unsafe { CStr::from_ptr(message) };
     let message = message_cstr.to_str().unwrap().to_string();
     
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        match client.handle_input_from_rn(&message) {
-            Ok(_) => true,
-            Err(_) => false,
-        }
-    } else {
-        false
-    }
+    client_status(handle, |client| handle_input(client, &message))
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_init_manager() -> Handle {
+    create_manager()
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_destroy_manager(handle: Handle) -> bool {
+    release_handle(handle, HandleKind::Manager)
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_connect_server(
+    handle: Handle,
+    server_id: *const c_char,
+    server_url: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let server_id = unsafe { CStr::from_ptr(server_id) }.to_str().unwrap().to_string();
+    let server_url = unsafe { CStr::from_ptr(server_url) }.to_str().unwrap().to_string();
+    
+    spawn_manager_call(handle, c_completion(completion, context), move |manager| async move {
+        connect_server_json(&manager, &server_id, &server_url).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_remove_server(
+    handle: Handle,
+    server_id: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let server_id = unsafe { CStr::from_ptr(server_id) }.to_str().unwrap().to_string();
+    
+    spawn_manager_call(handle, c_completion(completion, context), move |manager| async move {
+        remove_server_json(&manager, &server_id).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_list_all_tools(
+    handle: Handle,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    spawn_manager_call(handle, c_completion(completion, context), move |manager| async move {
+        list_all_tools_json(&manager).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_call_server_tool(
+    handle: Handle,
+    tool_name: *const c_char,
+    parameters_json: *const c_char,
+    completion: CompletionFn,
+    context: *mut c_void,
+) {
+    let tool_name = unsafe { CStr::from_ptr(tool_name) }.to_str().unwrap().to_string();
+    let parameters_json = unsafe { CStr::from_ptr(parameters_json) }.to_str().unwrap().to_string();
+    
+    spawn_manager_call(handle, c_completion(completion, context), move |manager| async move {
+        call_server_tool_json(&manager, &tool_name, &parameters_json).await
+    });
+}
+
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn mcp_get_server_states(handle: Handle) -> *mut c_char {
+    CString::new(with_manager(handle, server_states_json)).unwrap().into_raw()
 }
 
 // iOS回调函数类型
 #[cfg(target_os = "ios")]
 type EventCallbackFn = extern "C" fn(*const c_char, *mut c_void);
 
+// iOS完成回调函数类型，结果字符串只在回调期间有效
+#[cfg(target_os = "ios")]
+type CompletionFn = extern "C" fn(*const c_char, *mut c_void);
+
+// iOS回调上下文指针
+//
+// 上下文由调用方持有并保证在回调可能被调用期间有效且可跨线程使用
+#[cfg(target_os = "ios")]
+struct CallbackContext(*mut c_void);
+
+#[cfg(target_os = "ios")]
+impl CallbackContext {
+    fn as_ptr(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+#[cfg(target_os = "ios")]
+unsafe impl Send for CallbackContext {}
+#[cfg(target_os = "ios")]
+unsafe impl Sync for CallbackContext {}
+
+// 将C回调包装为完成回调，成功但没有结果时传入空指针
+//
+// 完成回调恰好调用一次，调用方可以在回调中释放上下文
+#[cfg(target_os = "ios")]
+fn c_completion(completion: CompletionFn, context: *mut c_void) -> Completion {
+    let context = CallbackContext(context);
+    Box::new(move |result: Option<String>| match result {
+        Some(result) => {
+            let c_result = CString::new(result).unwrap();
+            completion(c_result.as_ptr(), context.as_ptr());
+        }
+        None => completion(std::ptr::null(), context.as_ptr()),
+    })
+}
+
 #[cfg(target_os = "ios")]
 #[no_mangle]
 pub extern "C" fn mcp_register_event_callback(
+    handle: Handle,
     event_name: *const c_char,
     callback: EventCallbackFn,
     context: *mut c_void,
 ) -> bool {
-    let event_name_cstr = unsafe { CStr::from_ptr(event_name) };
-    let event_name = event_name_cstr.to_str().unwrap().to_string();
-    
-    let callback_box = Box::new(move |data: String| {
+    let event_name = unsafe { CStr::from_ptr(event_name) }.to_str().unwrap().to_string();
+    let context = CallbackContext(context);
+    let callback_box = Arc::new(move |data: String| {
         let c_data = CString::new(data).unwrap();
-        unsafe {
-            callback(c_data.as_ptr(), context);
-        }
+        callback(c_data.as_ptr(), context.as_ptr());
     });
     
-    register_event_callback(&event_name, callback_box);
-    
-    // 为MCP客户端设置回调
-    let mut client_guard = MCP_CLIENT.lock().unwrap();
-    if let Some(client) = client_guard.as_mut() {
-        let callback_id = format!("rn_callback_{}", event_name);
-        client.set_rn_callback(RNCallback {
-            callback_id: callback_id.clone(),
-            handler: Arc::new(move |json| {
-                emit_event(&event_name, &json);
-            }),
-        });
-    }
-    
-    true
+    // 客户端句柄和管理器句柄都可以注册
+    register_event_callback(handle, &event_name, callback_box)
 }
 
 // 释放字符串（iOS平台需要）
@@ -716,4 +1857,4 @@ pub extern "C" fn mcp_free_string(ptr: *mut c_char) {
             let _ = CString::from_raw(ptr);
         }
     }
-} 
\ No newline at end of file
+} 
@@ -1,9 +1,26 @@
+use crate::mcp::jsonrpc::{
+    decode_frame, encode_frame, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, RequestId,
+    WireFormat, LEGACY_HANDSHAKE_ID, LEGACY_PROTOCOL_VERSION,
+};
+use crate::mcp::schema::{self, SchemaViolation};
+use crate::mcp::transport::{self, ConnectOptions, Transport};
 use crate::mcp::protocol::{
-    McpMessage, McpResponse, McpResource, McpServerInfo, McpTool, parse_mcp_message, serialize_mcp_message,
-    text_content
+    ClientCapabilities, CompleteResult, CompletionReference, CompletionResult, CreateMessageParams, ElicitAction, ElicitRequestParams,
+    ElicitResult, ListChangedCapability, McpRoot, CreateMessageResult, GetPromptResult, InitializeParams, InitializeResult, ListPromptsResult,
+    ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, McpImplementation, McpPrompt, McpResource, McpResourceInfo, McpResourceTemplate, McpResponse,
+    LoggingLevel, McpLogMessage, McpServerInfo, McpTool, PaginatedResult, ProgressParams, ServerCapabilities, LATEST_PROTOCOL_VERSION,
+    SUPPORTED_PROTOCOL_VERSIONS,
 };
-use std::collections::HashMap;
+use serde_json::{json, Value};
+use crate::mcp::jsonrpc::{error_codes, JsonRpcError};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -17,9 +34,17 @@ pub enum McpError {
     CommunicationError(String),
     ProtocolError(String),
     ServerError { code: String, message: String },
+    IncompatibleVersion { requested: String, received: String },
     Timeout,
+    Cancelled,
     Disconnected,
     InternalError(String),
+    /// 工具参数未通过inputSchema校验，请求未发送
+    InvalidArguments { tool: String, violations: Vec<SchemaViolation> },
+    /// 提交给服务器的内容未通过schema校验，未发送
+    InvalidContent { violations: Vec<SchemaViolation> },
+    /// 管理器中没有该ID的服务器
+    UnknownServer(String),
 }
 
 impl fmt::Display for McpError {
@@ -29,9 +54,22 @@ impl fmt::Display for McpError {
             McpError::CommunicationError(msg) => write!(f, "通信错误: {}", msg),
             McpError::ProtocolError(msg) => write!(f, "协议错误: {}", msg),
             McpError::ServerError { code, message } => write!(f, "服务器错误 [{}]: {}", code, message),
+            McpError::IncompatibleVersion { requested, received } => {
+                write!(f, "协议版本不兼容: 客户端请求 {}, 服务器返回 {}", requested, received)
+            }
             McpError::Timeout => write!(f, "操作超时"),
+            McpError::Cancelled => write!(f, "请求已取消"),
             McpError::Disconnected => write!(f, "连接已断开"),
             McpError::InternalError(msg) => write!(f, "内部错误: {}", msg),
+            McpError::InvalidArguments { tool, violations } => {
+                let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                write!(f, "工具 {} 的参数无效: {}", tool, details.join("; "))
+            }
+            McpError::InvalidContent { violations } => {
+                let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                write!(f, "提交的内容无效: {}", details.join("; "))
+            }
+            McpError::UnknownServer(server_id) => write!(f, "未知的服务器: {}", server_id),
         }
     }
 }
@@ -45,12 +83,17 @@ type Result<T> = std::result::Result<T, McpError>;
 /// 用于与React Native交互的回调类型
 #[derive(Clone)]
 pub struct RNCallback {
-    callback_id: String, 
+    pub(crate) callback_id: String, 
     // 实际环境中会使用React Native FFI绑定，这里简化为函数指针
-    #[allow(dead_code)]
-    handler: Arc<dyn Fn(String) + Send + Sync>,
+    pub(crate) handler: Arc<dyn Fn(String) + Send + Sync>,
 }
 
+/// 服务器请求处理器返回的Future
+pub type HandlerFuture = Pin<Box<dyn Future<Output = Result<Value>> + Send>>;
+
+/// 服务器发起请求的处理器，参数为请求的params，返回值作为响应结果
+pub type ServerRequestHandler = Arc<dyn Fn(Option<Value>) -> HandlerFuture + Send + Sync>;
+
 /// 用于与React Native交互的事件类型
 #[derive(Serialize, Deserialize)]
 pub enum McpEvent {
@@ -65,11 +108,66 @@ pub enum McpEvent {
         request_id: String,
         uri: String,
     },
+    /// 服务器工具列表已变更
+    ToolsChanged {
+        tools: Vec<McpTool>,
+    },
+    /// 服务器资源列表已变更
+    ResourcesChanged {
+        resources: Vec<McpResourceInfo>,
+    },
+    /// 已订阅的资源内容已更新
+    ResourceUpdated {
+        uri: String,
+    },
+    /// 服务器提示词列表已变更
+    PromptsChanged {
+        prompts: Vec<McpPrompt>,
+    },
+    /// 服务器请求需要由React Native处理，处理结果通过`respond_to_server_request`返回
+    ServerRequest {
+        request_id: String,
+        method: String,
+        params: Option<Value>,
+    },
+    /// 转发给React Native的服务器请求或elicitation已被服务器取消或等待超时，之后的回复会被忽略
+    ServerRequestCancelled {
+        request_id: String,
+    },
+    /// 服务器日志
+    ServerLog {
+        level: LoggingLevel,
+        logger: Option<String>,
+        data: Value,
+    },
+    /// 请求进度更新
+    Progress {
+        call_id: String,
+        progress: f64,
+        total: Option<f64>,
+        message: Option<String>,
+    },
+    /// 服务器请求用户填写信息，处理结果通过`respond_to_elicitation`返回
+    Elicitation {
+        request_id: String,
+        message: String,
+        requested_schema: Value,
+    },
     /// 连接状态变更
     ConnectionState {
         connected: bool,
         server_name: Option<String>,
     },
+    /// 连接意外断开，等待delay_ms后进行第attempt次重连
+    Reconnecting {
+        attempt: usize,
+        delay_ms: u64,
+    },
+    /// 重连成功，会话已恢复
+    Reconnected {
+        attempt: usize,
+        server_name: Option<String>,
+    },
     /// 发生错误
     Error {
         code: String,
@@ -77,431 +175,2520 @@ pub enum McpEvent {
     },
 }
 
-// ===== MCP客户端 =====
+/// 将处理服务器请求时的错误映射为JSON-RPC错误
+fn to_jsonrpc_error(error: &McpError) -> JsonRpcError {
+    match error {
+        // 宿主返回的错误码为数字时原样传回服务器
+        McpError::ServerError { code, message } => match code.parse::<i64>() {
+            Ok(code) => JsonRpcError::new(code, message),
+            Err(_) => JsonRpcError::new(error_codes::INTERNAL_ERROR, &format!("[{}] {}", code, message)),
+        },
+        McpError::InvalidArguments { .. } => JsonRpcError::new(error_codes::INVALID_PARAMS, &error.to_string()),
+        McpError::ProtocolError(_) => JsonRpcError::new(error_codes::INVALID_PARAMS, &error.to_string()),
+        // 用户拒绝或取消 (MCP约定使用-1)
+        McpError::Cancelled => JsonRpcError::new(-1, "用户拒绝了该请求"),
+        _ => JsonRpcError::new(error_codes::INTERNAL_ERROR, &error.to_string()),
+    }
+}
 
-/// MCP客户端实现
-pub struct McpClient {
-    /// 连接状态
-    connected: bool,
+/// 解析elicitation请求参数，schema不符合协议限制时返回错误
+fn parse_elicit_params(params: Option<Value>) -> Result<ElicitRequestParams> {
+    let params: ElicitRequestParams = serde_json::from_value(params.unwrap_or(Value::Null))
+        .map_err(|e| McpError::ProtocolError(format!("无效的elicitation请求参数: {}", e)))?;
+    
+    let violations = schema::check_restricted_schema(&params.requested_schema);
+    if !violations.is_empty() {
+        let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        return Err(McpError::ProtocolError(format!("不支持的requestedSchema: {}", details.join("; "))));
+    }
+    
+    Ok(params)
+}
+
+/// 按请求的schema校验用户的回复，非accept时丢弃内容
+fn check_elicit_result(requested_schema: &Value, mut result: ElicitResult) -> Result<ElicitResult> {
+    if result.action != ElicitAction::Accept {
+        result.content = None;
+        return Ok(result);
+    }
+    
+    let content = Value::Object(result.content.clone().unwrap_or_default());
+    let violations = schema::validate(requested_schema, &content);
+    if !violations.is_empty() {
+        return Err(McpError::InvalidContent { violations });
+    }
+    
+    Ok(result)
+}
+
+// ===== 请求选项 =====
+
+/// 默认请求超时时间
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 分页列表请求的最大页数，防止服务器返回循环游标
+const MAX_LIST_PAGES: usize = 100;
+
+/// 进度回调，在收到对应请求的`notifications/progress`时调用
+pub type ProgressCallback = Arc<dyn Fn(&ProgressParams) + Send + Sync>;
+
+/// 单次请求的选项
+#[derive(Clone, Default)]
+pub struct RequestOptions {
+    /// 请求ID，为None时自动生成 (可用于之后调用`cancel`)
+    pub request_id: Option<String>,
+    /// 超时时间，为None时使用客户端默认值
+    pub timeout: Option<Duration>,
+    /// 进度回调 (可选)
+    pub progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for RequestOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestOptions")
+            .field("request_id", &self.request_id)
+            .field("timeout", &self.timeout)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// 服务器日志的输出目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LogSink {
+    /// 作为`McpEvent::ServerLog`事件发送给React Native
+    RnEvent,
+    /// 写入`log`日志库
+    LogCrate,
+    /// 保存在内存环形缓冲区中，超出容量时丢弃最旧的记录
+    RingBuffer { capacity: usize },
+}
+
+/// 连接健康检查配置
+#[derive(Debug, Clone)]
+pub struct HealthCheckConfig {
+    /// 发送ping的间隔
+    pub interval: Duration,
+    /// 单次ping的超时时间
+    pub timeout: Duration,
+    /// 连续失败达到该次数后视为连接已断开
+    pub failure_threshold: u32,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        HealthCheckConfig {
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+            failure_threshold: 3,
+        }
+    }
+}
+
+/// 重试配置 (指数退避)
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// 最大重试次数
+    pub max_retries: usize,
+    /// 首次重试前的延迟
+    pub initial_delay_ms: u64,
+    /// 每次重试后延迟的增长倍数
+    pub backoff_factor: f64,
+    /// 延迟上限
+    pub max_delay_ms: u64,
+}
+
+impl RetryConfig {
+    /// 计算下一次重试的延迟时间
+    pub fn next_delay_ms(&self, delay_ms: u64) -> u64 {
+        std::cmp::min(
+            (delay_ms as f64 * self.backoff_factor) as u64,
+            self.max_delay_ms,
+        )
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: 3,
+            initial_delay_ms: 500,
+            backoff_factor: 1.5,
+            max_delay_ms: 5000,
+        }
+    }
+}
+
+/// 重连时对断开前未完成请求的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InFlightPolicy {
+    /// 连接断开时立即以`McpError::Disconnected`结束
+    Fail,
+    /// 保留请求，重连成功后重新发送 (仍受原超时时间限制)
+    Replay,
+}
+
+/// 自动重连配置
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// 重连的退避策略
+    pub retry: RetryConfig,
+    /// 未完成请求的处理方式
+    pub in_flight: InFlightPolicy,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        ReconnectConfig {
+            retry: RetryConfig {
+                max_retries: 10,
+                initial_delay_ms: 1000,
+                backoff_factor: 2.0,
+                max_delay_ms: 30000,
+            },
+            in_flight: InFlightPolicy::Fail,
+        }
+    }
+}
+
+/// 连接健康状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionHealth {
+    /// 是否已连接
+    pub connected: bool,
+    /// 最近一次ping的往返延迟 (毫秒)
+    pub latency_ms: Option<u64>,
+    /// 连续失败的ping次数
+    pub consecutive_failures: u32,
+}
+
+/// 健康检查的统计数据
+#[derive(Default)]
+struct HealthState {
+    /// 最近一次ping的往返延迟
+    latency: Option<Duration>,
+    /// 连续失败次数
+    consecutive_failures: u32,
+}
+
+/// 进行中请求的进度路由
+struct ProgressRoute {
+    /// 对应的调用ID
+    call_id: String,
+    /// 调用方提供的进度回调
+    callback: Option<ProgressCallback>,
+}
+
+/// 请求结束时移除进度路由
+struct ProgressGuard {
+    shared: Arc<ClientShared>,
+    token: String,
+}
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        self.shared.progress_routes.lock().unwrap().remove(&self.token);
+    }
+}
+
+/// 等待React Native回复期间的清理守卫
+///
+/// 超时或服务器取消请求导致等待被丢弃时，移除待回复项并通知React Native
+struct HostRequestGuard<'a> {
+    shared: &'a ClientShared,
+    request_id: String,
+}
+
+impl Drop for HostRequestGuard<'_> {
+    fn drop(&mut self) {
+        // 已回复或连接断开时待回复项已被移除，无需通知
+        if self.shared.host_requests.lock().unwrap().remove(&self.request_id).is_some() {
+            self.shared.send_event_to_rn(McpEvent::ServerRequestCancelled {
+                request_id: self.request_id.clone(),
+            });
+        }
+    }
+}
+
+// ===== 共享状态 =====
+
+/// 等待响应的请求
+struct PendingRequest {
+    /// 响应发送端
+    sender: oneshot::Sender<Result<Value>>,
+    /// 原始请求，重连后重新发送时使用
+    request: JsonRpcRequest,
+}
+
+/// 待处理请求表
+type PendingResponses = HashMap<RequestId, PendingRequest>;
+
+/// 握手后得到的会话信息
+#[derive(Default)]
+struct SessionInfo {
     /// 服务器信息
     server_info: Option<McpServerInfo>,
+    /// 服务器能力
+    server_capabilities: Option<ServerCapabilities>,
+    /// 协商后的协议版本
+    protocol_version: Option<String>,
+    /// 握手时声明的客户端能力
+    client_capabilities: Option<ClientCapabilities>,
+}
+
+/// 客户端与后台消息循环共享的状态
+struct ClientShared {
+    /// 连接状态
+    connected: AtomicBool,
     /// 待处理的响应
-    pending_responses: Arc<Mutex<HashMap<String, oneshot::Sender<Result<McpMessage>>>>>,
-    /// 用于接收转发的MCP消息的通道
-    receiver: Option<Arc<Mutex<mpsc::Receiver<String>>>>,
-    /// 用于发送消息到服务器的通道
-    sender: Option<mpsc::Sender<String>>,
+    pending_responses: Mutex<PendingResponses>,
+    /// 当前会话信息 (握手后可用，重连时更新)
+    session: Mutex<SessionInfo>,
+    /// 当前连接使用的线路格式，握手时确定，连接期间保持不变
+    wire_format: Mutex<WireFormat>,
+    /// 宿主选择的线路格式，握手请求按该格式发送
+    preferred_format: Mutex<WireFormat>,
+    /// 服务器握手响应所使用的格式，由消息循环记录
+    handshake_format: Mutex<Option<WireFormat>>,
     /// 与React Native的交互回调
-    rn_callback: Option<RNCallback>,
+    rn_callback: Mutex<Option<RNCallback>>,
+    /// 默认请求超时时间
+    request_timeout: Mutex<Duration>,
+    /// 工具列表缓存
+    tools: Mutex<Option<Vec<McpTool>>>,
+    /// 资源列表缓存
+    resources: Mutex<Option<Vec<McpResourceInfo>>>,
+    /// 资源模板列表缓存
+    resource_templates: Mutex<Option<Vec<McpResourceTemplate>>>,
+    /// 提示词列表缓存
+    prompts: Mutex<Option<Vec<McpPrompt>>>,
+    /// 已订阅的资源URI
+    subscriptions: Mutex<HashSet<String>>,
+    /// 进度令牌到进行中请求的映射
+    progress_routes: Mutex<HashMap<String, ProgressRoute>>,
+    /// 健康检查统计
+    health: Mutex<HealthState>,
+    /// 健康检查判定连接失效的原因，消息循环结束时取出
+    close_reason: Mutex<Option<McpError>>,
+    /// 结束当前消息循环的通道，健康检查判定连接失效时使用
+    connection_lost: Mutex<Option<oneshot::Sender<()>>>,
+    /// 自动重连配置，为None时不重连
+    reconnect: Mutex<Option<ReconnectConfig>>,
+    /// 按地址连接时使用的附加选项
+    connect_options: Mutex<ConnectOptions>,
+    /// 服务器日志输出目标
+    log_sinks: Mutex<Vec<LogSink>>,
+    /// 服务器日志环形缓冲区
+    log_buffer: Mutex<VecDeque<McpLogMessage>>,
+    /// 公开给服务器的根目录，为None时不声明roots能力
+    roots: Mutex<Option<Vec<McpRoot>>>,
+    /// 服务器请求处理器 (按方法名)
+    request_handlers: Mutex<HashMap<String, ServerRequestHandler>>,
+    /// 正在处理的服务器请求，收到`notifications/cancelled`时通过通道中止处理
+    server_requests: Mutex<HashMap<RequestId, oneshot::Sender<()>>>,
+    /// 等待React Native回复的服务器请求
+    host_requests: Mutex<HashMap<String, oneshot::Sender<Result<Value>>>>,
+    /// 等待React Native回复的elicitation请求及其schema
+    elicitations: Mutex<HashMap<String, Value>>,
 }
 
-impl McpClient {
-    /// 创建新的MCP客户端
-    pub fn new() -> Self {
-        McpClient {
-            connected: false,
-            server_info: None,
-            pending_responses: Arc::new(Mutex::new(HashMap::new())),
-            receiver: None,
-            sender: None,
-            rn_callback: None,
+impl ClientShared {
+    fn new() -> Self {
+        ClientShared {
+            connected: AtomicBool::new(false),
+            pending_responses: Mutex::new(HashMap::new()),
+            session: Mutex::new(SessionInfo::default()),
+            wire_format: Mutex::new(WireFormat::default()),
+            preferred_format: Mutex::new(WireFormat::default()),
+            handshake_format: Mutex::new(None),
+            rn_callback: Mutex::new(None),
+            request_timeout: Mutex::new(DEFAULT_REQUEST_TIMEOUT),
+            tools: Mutex::new(None),
+            resources: Mutex::new(None),
+            resource_templates: Mutex::new(None),
+            prompts: Mutex::new(None),
+            subscriptions: Mutex::new(HashSet::new()),
+            progress_routes: Mutex::new(HashMap::new()),
+            health: Mutex::new(HealthState::default()),
+            close_reason: Mutex::new(None),
+            connection_lost: Mutex::new(None),
+            reconnect: Mutex::new(Some(ReconnectConfig::default())),
+            connect_options: Mutex::new(ConnectOptions::default()),
+            log_sinks: Mutex::new(vec![LogSink::RnEvent]),
+            log_buffer: Mutex::new(VecDeque::new()),
+            roots: Mutex::new(None),
+            request_handlers: Mutex::new(HashMap::new()),
+            server_requests: Mutex::new(HashMap::new()),
+            host_requests: Mutex::new(HashMap::new()),
+            elicitations: Mutex::new(HashMap::new()),
         }
     }
     
-    /// 设置React Native回调
-    pub fn set_rn_callback(&mut self, callback: RNCallback) {
-        self.rn_callback = Some(callback);
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
     }
     
-    /// 处理来自React Native的输入消息
-    pub fn handle_input_from_rn(&mut self, message: &str) -> Result<()> {
-        let mcp_message = parse_mcp_message(message)
-            .map_err(|e| McpError::ProtocolError(format!("解析消息失败: {}", e)))?;
-        
-        self.handle_incoming_message(mcp_message)
+    fn wire_format(&self) -> WireFormat {
+        *self.wire_format.lock().unwrap()
     }
     
-    /// 处理传入的MCP消息
-    fn handle_incoming_message(&mut self, message: McpMessage) -> Result<()> {
-        match &message {
-            McpMessage::ToolResponse { call_id, .. } | 
-            McpMessage::ResourceResponse { request_id: call_id, .. } => {
-                // 查找并完成待处理的响应
-                let mut pending = self.pending_responses.lock().unwrap();
-                if let Some(sender) = pending.remove(call_id) {
-                    let _ = sender.send(Ok(message.clone()));
-                }
-            },
-            McpMessage::Error { reference_id: Some(id), code, message: error_msg } => {
-                // 处理针对特定请求的错误
-                let mut pending = self.pending_responses.lock().unwrap();
-                if let Some(sender) = pending.remove(id) {
-                    let _ = sender.send(Err(McpError::ServerError { 
-                        code: code.clone(), 
-                        message: error_msg.clone() 
-                    }));
-                }
-            },
-            McpMessage::Handshake { server_info, .. } => {
-                // 处理握手响应
-                self.connected = true;
-                self.server_info = server_info.clone();
-                
-                // 通知React Native连接状态变更
-                self.send_event_to_rn(McpEvent::ConnectionState { 
-                    connected: true, 
-                    server_name: self.server_info.as_ref().map(|i| i.name.clone()),
-                });
-            },
-            McpMessage::Error { reference_id: None, code, message: error_msg } => {
-                // 处理一般错误
-                self.send_event_to_rn(McpEvent::Error { 
-                    code: code.clone(), 
-                    message: error_msg.clone() 
-                });
-            },
-            _ => {
-                // 其他消息类型，可能需要添加更多处理逻辑
-            }
-        }
-        
-        Ok(())
+    fn request_timeout(&self) -> Duration {
+        *self.request_timeout.lock().unwrap()
+    }
+    
+    fn server_info(&self) -> Option<McpServerInfo> {
+        self.session.lock().unwrap().server_info.clone()
+    }
+    
+    fn server_capabilities(&self) -> Option<ServerCapabilities> {
+        self.session.lock().unwrap().server_capabilities.clone()
     }
     
     /// 向React Native发送事件
     fn send_event_to_rn(&self, event: McpEvent) {
-        if let Some(callback) = &self.rn_callback {
+        if let Some(callback) = self.rn_callback.lock().unwrap().as_ref() {
             if let Ok(json) = serde_json::to_string(&event) {
-                // 在实际实现中，这里会调用callback.handler
-                println!("向RN发送事件: {} - {}", callback.callback_id, json);
+                (callback.handler)(json);
             }
         }
     }
     
-    /// 连接到MCP服务器
-    pub async fn connect(&mut self, server_url: &str) -> Result<()> {
-        // 创建通信通道
-        let (tx, rx) = mpsc::channel(10);
-        self.sender = Some(tx);
-        self.receiver = Some(Arc::new(Mutex::new(rx)));
-        
-        // 在实际实现中，这里会建立WebSocket或HTTP连接
-        println!("连接到MCP服务器: {}", server_url);
-        
-        // 发送握手消息
-        let handshake = McpMessage::Handshake {
-            version: "1.0".to_string(),
-            server_info: None,
+    /// 按当前线路格式编码并发送消息
+    async fn send<T: Transport>(&self, transport: &T, message: &JsonRpcMessage) -> Result<()> {
+        let json = match encode_frame(message, self.wire_format())
+            .map_err(|e| McpError::ProtocolError(format!("序列化消息失败: {}", e)))? {
+            Some(json) => json,
+            // 当前线路格式无法表示该消息 (如旧版格式下的通知)，直接丢弃
+            None => return Ok(()),
         };
         
-        self.send_message(handshake).await?;
-        
-        // 启动消息处理循环
-        self.spawn_message_handler();
-        
-        Ok(())
-    }
-    
-    /// 启动消息处理循环
-    fn spawn_message_handler(&self) {
-        // 克隆必要的数据用于消息处理任务
-        let receiver = self.receiver.as_ref().unwrap().clone();
-        let pending_responses = self.pending_responses.clone();
-        
-        // 在实际应用中，这会在单独的线程或任务中运行
-        // 为简化展示，这里只展示逻辑结构
-        std::thread::spawn(move || {
-            let mut rx = receiver.lock().unwrap();
-            
-            while let Some(msg) = rx.try_recv().ok() {
-                match parse_mcp_message(&msg) {
-                    Ok(mcp_msg) => {
-                        match &mcp_msg {
-                            McpMessage::ToolResponse { call_id, .. } | 
-                            McpMessage::ResourceResponse { request_id: call_id, .. } => {
-                                // 查找并完成待处理的响应
-                                let mut pending = pending_responses.lock().unwrap();
-                                if let Some(sender) = pending.remove(call_id) {
-                                    let _ = sender.send(Ok(mcp_msg));
-                                }
-                            },
-                            McpMessage::Error { reference_id: Some(id), code, message } => {
-                                // 处理针对特定请求的错误
-                                let mut pending = pending_responses.lock().unwrap();
-                                if let Some(sender) = pending.remove(id) {
-                                    let _ = sender.send(Err(McpError::ServerError { 
-                                        code: code.clone(), 
-                                        message: message.clone() 
-                                    }));
-                                }
-                            },
-                            _ => {
-                                // 处理其他消息类型
-                            }
-                        }
-                    },
-                    Err(e) => {
-                        println!("解析消息失败: {}", e);
-                    }
-                }
-            }
-        });
+        transport.send(json).await
     }
     
-    /// 发送消息到服务器
-    async fn send_message(&self, message: McpMessage) -> Result<()> {
-        if !self.connected && !matches!(message, McpMessage::Handshake { .. }) {
-            return Err(McpError::Disconnected);
-        }
+    /// 完成与响应ID对应的待处理请求
+    fn complete_pending(&self, response: JsonRpcResponse) {
+        let id = match response.id {
+            Some(id) => id,
+            None => return,
+        };
         
-        let json = serialize_mcp_message(&message)
-            .map_err(|e| McpError::ProtocolError(format!("序列化消息失败: {}", e)))?;
-            
-        if let Some(sender) = &self.sender {
-            sender.send(json).await
-                .map_err(|_| McpError::CommunicationError("发送消息失败".to_string()))?;
-            Ok(())
-        } else {
-            Err(McpError::Disconnected)
+        let mut pending = self.pending_responses.lock().unwrap();
+        if let Some(PendingRequest { sender, .. }) = pending.remove(&id) {
+            let result = match response.error {
+                Some(error) => Err(McpError::ServerError { 
+                    code: error.code_string(), 
+                    message: error.message,
+                }),
+                None => Ok(response.result.unwrap_or(Value::Null)),
+            };
+            let _ = sender.send(result);
         }
     }
     
-    /// 调用工具
-    pub async fn call_tool(&self, name: &str, parameters: HashMap<String, serde_json::Value>) -> Result<McpResponse> {
-        if !self.connected {
+    /// 发送请求并等待对应的响应结果
+    ///
+    /// 超时或调用方放弃等待时会移除待处理项，并向服务器发送`notifications/cancelled`
+    async fn request<T: Transport>(
+        self: &Arc<Self>,
+        transport: &Arc<T>,
+        id: RequestId,
+        method: &str,
+        params: Option<Value>,
+        timeout: Duration,
+    ) -> Result<Value> {
+        // 握手完成前只允许发送initialize请求
+        let is_handshake = method == "initialize";
+        if !self.is_connected() && !is_handshake {
             return Err(McpError::Disconnected);
         }
         
-        // 创建唯一ID
-        let call_id = Uuid::new_v4().to_string();
-        
         // 设置接收通道
         let (tx, rx) = oneshot::channel();
+        let request = JsonRpcRequest::new(id.clone(), method, params);
         {
             let mut pending = self.pending_responses.lock().unwrap();
-            pending.insert(call_id.clone(), tx);
+            pending.insert(id.clone(), PendingRequest { sender: tx, request: request.clone() });
         }
         
-        // 创建并发送工具调用消息
-        let message = McpMessage::ToolCall {
-            call_id: call_id.clone(),
-            name: name.to_string(),
-            parameters,
+        // 插入待处理项后立即创建守卫，发送期间被丢弃也会移除待处理项
+        let mut guard = PendingGuard {
+            shared: self.clone(),
+            transport: transport.clone(),
+            id: Some(id.clone()),
+            cancellable: false,
         };
         
-        // 通知React Native有工具调用
-        if let McpMessage::ToolCall { call_id, name, parameters } = &message {
-            self.send_event_to_rn(McpEvent::ToolCall { 
-                call_id: call_id.clone(), 
-                name: name.clone(), 
-                parameters: parameters.clone() 
-            });
+        if let Err(e) = self.send(&**transport, &JsonRpcMessage::Request(request)).await {
+            guard.disarm();
+            self.take_pending(&id);
+            return Err(e);
         }
         
-        self.send_message(message).await?;
+        // 请求已发出，放弃时需要通知服务器；协议规定initialize请求不可取消
+        guard.cancellable = !is_handshake;
         
         // 等待响应
-        match rx.await {
-            Ok(result) => {
-                match result {
-                    Ok(McpMessage::ToolResponse { response, .. }) => Ok(response),
-                    Ok(_) => Err(McpError::ProtocolError("收到非预期响应类型".to_string())),
-                    Err(e) => Err(e),
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => {
+                guard.disarm();
+                result
+            },
+            Ok(Err(_)) => {
+                guard.disarm();
+                Err(McpError::InternalError("响应通道已关闭".to_string()))
+            },
+            Err(_) => {
+                guard.disarm();
+                if self.take_pending(&id).is_some() && !is_handshake {
+                    let _ = self.send_cancelled(&**transport, &id, "请求超时").await;
                 }
+                Err(McpError::Timeout)
             },
-            Err(_) => Err(McpError::InternalError("响应通道已关闭".to_string())),
         }
     }
     
-    /// 请求资源
-    pub async fn request_resource(&self, uri: &str) -> Result<McpResource> {
-        if !self.connected {
-            return Err(McpError::Disconnected);
-        }
-        
-        // 创建唯一ID
-        let request_id = Uuid::new_v4().to_string();
+    /// 逐页请求列表直到没有下一页
+    async fn list_all<T: Transport, R: PaginatedResult>(
+        self: &Arc<Self>,
+        transport: &Arc<T>,
+        method: &str,
+    ) -> Result<Vec<R::Item>> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
         
-        // 设置接收通道
-        let (tx, rx) = oneshot::channel();
-        {
-            let mut pending = self.pending_responses.lock().unwrap();
-            pending.insert(request_id.clone(), tx);
+        for _ in 0..MAX_LIST_PAGES {
+            let params = cursor.as_ref().map(|cursor| json!({ "cursor": cursor }));
+            let result = self.request(
+                transport,
+                Uuid::new_v4().to_string().into(),
+                method,
+                params,
+                self.request_timeout(),
+            ).await?;
+            let page: R = serde_json::from_value(result)
+                .map_err(|e| McpError::ProtocolError(format!("解析{}响应失败: {}", method, e)))?;
+            
+            let (page_items, next_cursor) = page.into_page();
+            items.extend(page_items);
+            
+            match next_cursor {
+                Some(next) if cursor.as_ref() != Some(&next) => cursor = Some(next),
+                _ => return Ok(items),
+            }
         }
         
-        // 创建并发送资源请求消息
-        let message = McpMessage::ResourceRequest {
-            request_id: request_id.clone(),
-            uri: uri.to_string(),
-        };
-        
-        // 通知React Native有资源请求
-        self.send_event_to_rn(McpEvent::ResourceRequest { 
-            request_id: request_id.clone(), 
-            uri: uri.to_string() 
-        });
-        
-        self.send_message(message).await?;
-        
-        // 等待响应
-        match rx.await {
-            Ok(result) => {
-                match result {
-                    Ok(McpMessage::ResourceResponse { resource, .. }) => Ok(resource),
-                    Ok(_) => Err(McpError::ProtocolError("收到非预期响应类型".to_string())),
-                    Err(e) => Err(e),
-                }
-            },
-            Err(_) => Err(McpError::InternalError("响应通道已关闭".to_string())),
-        }
+        Err(McpError::ProtocolError(format!("{}分页数超过上限", method)))
     }
     
-    /// 断开连接
-    pub async fn disconnect(&mut self) -> Result<()> {
-        self.connected = false;
-        self.server_info = None;
-        
-        // 清理通道
-        self.sender = None;
-        self.receiver = None;
-        
-        // 通知React Native连接已断开
-        self.send_event_to_rn(McpEvent::ConnectionState { 
-            connected: false, 
-            server_name: None,
-        });
-        
-        Ok(())
+    /// 重新获取工具列表并更新缓存
+    async fn refresh_tools<T: Transport>(self: &Arc<Self>, transport: &Arc<T>) -> Result<Vec<McpTool>> {
+        let tools = self.list_all::<T, ListToolsResult>(transport, "tools/list").await?;
+        *self.tools.lock().unwrap() = Some(tools.clone());
+        Ok(tools)
     }
     
-    /// 获取服务器信息
-    pub fn get_server_info(&self) -> Option<McpServerInfo> {
-        self.server_info.clone()
+    /// 重新获取资源列表并更新缓存
+    async fn refresh_resources<T: Transport>(self: &Arc<Self>, transport: &Arc<T>) -> Result<Vec<McpResourceInfo>> {
+        let resources = self.list_all::<T, ListResourcesResult>(transport, "resources/list").await?;
+        *self.resources.lock().unwrap() = Some(resources.clone());
+        Ok(resources)
     }
     
-    /// 检查是否已连接
-    pub fn is_connected(&self) -> bool {
-        self.connected
+    /// 重新获取资源模板列表并更新缓存
+    async fn refresh_resource_templates<T: Transport>(
+        self: &Arc<Self>,
+        transport: &Arc<T>,
+    ) -> Result<Vec<McpResourceTemplate>> {
+        let templates = self.list_all::<T, ListResourceTemplatesResult>(transport, "resources/templates/list").await?;
+        *self.resource_templates.lock().unwrap() = Some(templates.clone());
+        Ok(templates)
     }
-}
-
-/// 为了示例的完整性，这里包含了一些FFI样板代码
-/// 实际应用中需要使用具体的React Native FFI绑定
-mod ffi {
-    use super::*;
     
-    #[no_mangle]
-    pub extern "C" fn mcp_client_create() -> *mut McpClient {
-        let client = Box::new(McpClient::new());
-        Box::into_raw(client)
+    /// 重新获取提示词列表并更新缓存
+    async fn refresh_prompts<T: Transport>(self: &Arc<Self>, transport: &Arc<T>) -> Result<Vec<McpPrompt>> {
+        let prompts = self.list_all::<T, ListPromptsResult>(transport, "prompts/list").await?;
+        *self.prompts.lock().unwrap() = Some(prompts.clone());
+        Ok(prompts)
     }
     
-    #[no_mangle]
-    pub extern "C" fn mcp_client_destroy(client: *mut McpClient) {
-        if !client.is_null() {
-            unsafe {
-                drop(Box::from_raw(client));
-            }
-        }
+    /// 清空工具、资源等列表缓存
+    fn clear_caches(&self) {
+        self.tools.lock().unwrap().take();
+        self.resources.lock().unwrap().take();
+        self.resource_templates.lock().unwrap().take();
+        self.prompts.lock().unwrap().take();
     }
     
-    #[no_mangle]
-    pub extern "C" fn mcp_client_connect(client: *mut McpClient, server_url: *const libc::c_char) -> bool {
-        if client.is_null() || server_url.is_null() {
-            return false;
-        }
-        
-        unsafe {
-            let client = &mut *client;
-            let server_url = std::ffi::CStr::from_ptr(server_url).to_string_lossy().into_owned();
-            
-            // 实际应用中应该使用适当的异步运行时来执行这个操作
-            // 这里只是示例
-            match futures::executor::block_on(client.connect(&server_url)) {
-                Ok(_) => true,
-                Err(_) => false,
-            }
-        }
+    /// 移除待处理请求，返回其响应发送端
+    fn take_pending(&self, id: &RequestId) -> Option<oneshot::Sender<Result<Value>>> {
+        self.pending_responses.lock().unwrap().remove(id).map(|pending| pending.sender)
     }
     
-    #[no_mangle]
-    pub extern "C" fn mcp_client_handle_input(client: *mut McpClient, message: *const libc::c_char) -> bool {
-        if client.is_null() || message.is_null() {
-            return false;
-        }
-        
-        unsafe {
-            let client = &mut *client;
-            let message = std::ffi::CStr::from_ptr(message).to_string_lossy().into_owned();
-            
-            match client.handle_input_from_rn(&message) {
-                Ok(_) => true,
-                Err(_) => false,
-            }
-        }
+    /// 通知服务器取消请求
+    async fn send_cancelled<T: Transport>(&self, transport: &T, id: &RequestId, reason: &str) -> Result<()> {
+        let notification = JsonRpcNotification::new(
+            "notifications/cancelled",
+            Some(json!({ "requestId": id, "reason": reason })),
+        );
+        self.send(transport, &JsonRpcMessage::Notification(notification)).await
     }
     
-    // 这里需要添加更多FFI函数来暴露客户端的其他功能...
-}
-
-// 为编译需要添加这些导入和模块，在实际应用中会使用真实的crate
-mod oneshot {
-    use std::sync::mpsc;
-    
-    pub struct Sender<T>(mpsc::Sender<T>);
-    
-    impl<T> Sender<T> {
-        pub fn send(self, t: T) -> Result<(), mpsc::SendError<T>> {
-            self.0.send(t)
+    /// 以连接断开错误结束所有待处理请求
+    fn fail_all_pending(&self) {
+        let mut pending = self.pending_responses.lock().unwrap();
+        for (_, pending) in pending.drain() {
+            let _ = pending.sender.send(Err(McpError::Disconnected));
         }
+        
+        self.fail_host_requests();
     }
     
-    pub struct Receiver<T>(mpsc::Receiver<T>);
-    
-    impl<T> Receiver<T> {
-        pub async fn await(self) -> Result<T, ()> {
-            self.0.recv().map_err(|_| ())
+    /// 以连接断开错误结束等待React Native回复的服务器请求
+    ///
+    /// 这些请求属于旧连接，重连后无法再回复给服务器
+    fn fail_host_requests(&self) {
+        let mut host_requests = self.host_requests.lock().unwrap();
+        for (_, sender) in host_requests.drain() {
+            let _ = sender.send(Err(McpError::Disconnected));
         }
     }
     
-    pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
-        let (tx, rx) = mpsc::channel();
-        (Sender(tx), Receiver(rx))
+    /// 在新连接上重新发送断开前未完成的请求
+    async fn replay_pending<T: Transport>(&self, transport: &T) -> Result<()> {
+        let requests: Vec<JsonRpcRequest> = self.pending_responses.lock().unwrap()
+            .values()
+            .filter(|pending| pending.request.method != "initialize")
+            .map(|pending| pending.request.clone())
+            .collect();
+        
+        for request in requests {
+            self.send(transport, &JsonRpcMessage::Request(request)).await?;
+        }
+        Ok(())
     }
-}
-
-mod mpsc {
-    use std::sync::mpsc;
-    
-    pub struct Sender<T>(mpsc::Sender<T>);
     
-    impl<T> Sender<T> {
-        pub async fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
-            self.0.send(t)
+    /// 分发一条传入消息
+    ///
+    /// 服务器请求在独立任务中处理并回复，避免阻塞消息循环；必须在tokio运行时中调用
+    fn dispatch<T: Transport>(self: &Arc<Self>, transport: &Arc<T>, message: JsonRpcMessage) {
+        match message {
+            JsonRpcMessage::Response(JsonRpcResponse { id: None, error: Some(error), .. }) => {
+                // 处理一般错误
+                self.send_event_to_rn(McpEvent::Error { 
+                    code: error.code_string(), 
+                    message: error.message,
+                });
+            },
+            JsonRpcMessage::Response(response) => {
+                // 查找并完成待处理的响应
+                self.complete_pending(response);
+            },
+            JsonRpcMessage::Request(request) => {
+                let id = request.id.clone();
+                let (cancel_sender, cancel_receiver) = oneshot::channel();
+                self.server_requests.lock().unwrap().insert(id.clone(), cancel_sender);
+                
+                let shared = self.clone();
+                let transport = transport.clone();
+                tokio::spawn(async move {
+                    let response = tokio::select! {
+                        response = shared.handle_server_request(request) => Some(response),
+                        // 服务器已取消该请求，按协议不再回复
+                        _ = cancel_receiver => None,
+                    };
+                    shared.server_requests.lock().unwrap().remove(&id);
+                    
+                    if let Some(response) = response {
+                        if let Err(e) = shared.send(&*transport, &JsonRpcMessage::Response(response)).await {
+                            log::warn!("回复服务器请求失败: {}", e);
+                        }
+                    }
+                });
+            },
+            JsonRpcMessage::Notification(notification) => {
+                self.handle_notification(transport, notification);
+            },
         }
     }
     
-    pub struct Receiver<T>(mpsc::Receiver<T>);
-    
-    impl<T> Receiver<T> {
-        pub fn try_recv(&mut self) -> Result<T, mpsc::TryRecvError> {
-            self.0.try_recv()
+    /// 处理服务器发起的请求
+    async fn handle_server_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        match request.method.as_str() {
+            "ping" => return JsonRpcResponse::success(request.id, json!({})),
+            "roots/list" => {
+                if let Some(roots) = self.roots.lock().unwrap().clone() {
+                    return JsonRpcResponse::success(request.id, json!({ "roots": roots }));
+                }
+            },
+            _ => {}
+        }
+        
+        let handler = self.request_handlers.lock().unwrap().get(&request.method).cloned();
+        let handler = match handler {
+            Some(handler) => handler,
+            None => {
+                return JsonRpcResponse::failure(
+                    Some(request.id),
+                    JsonRpcError::new(error_codes::METHOD_NOT_FOUND, &format!("不支持的方法: {}", request.method)),
+                );
+            }
+        };
+        
+        match handler(request.params).await {
+            Ok(result) => JsonRpcResponse::success(request.id, result),
+            Err(e) => JsonRpcResponse::failure(Some(request.id), to_jsonrpc_error(&e)),
         }
     }
     
-    pub fn channel<T>(_capacity: usize) -> (Sender<T>, Receiver<T>) {
-        let (tx, rx) = mpsc::channel();
-        (Sender(tx), Receiver(rx))
+    /// 将服务器请求转发给React Native并等待其回复
+    async fn forward_to_rn(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let request_id = Uuid::new_v4().to_string();
+        let event = McpEvent::ServerRequest {
+            request_id: request_id.clone(),
+            method: method.to_string(),
+            params,
+        };
+        self.wait_for_host(request_id, event).await
+    }
+    
+    /// 将elicitation请求转发给React Native并等待用户处理
+    async fn forward_elicitation_to_rn(&self, params: ElicitRequestParams) -> Result<Value> {
+        let request_id = Uuid::new_v4().to_string();
+        self.elicitations.lock().unwrap().insert(request_id.clone(), params.requested_schema.clone());
+        
+        let event = McpEvent::Elicitation {
+            request_id: request_id.clone(),
+            message: params.message,
+            requested_schema: params.requested_schema,
+        };
+        let result = self.wait_for_host(request_id.clone(), event).await;
+        self.elicitations.lock().unwrap().remove(&request_id);
+        result
+    }
+    
+    /// 向React Native发送事件，等待其通过请求ID回复
+    ///
+    /// 等待时间受默认请求超时限制，超时后向服务器返回`McpError::Timeout`
+    async fn wait_for_host(&self, request_id: String, event: McpEvent) -> Result<Value> {
+        if self.rn_callback.lock().unwrap().is_none() {
+            return Err(McpError::InternalError("未设置React Native回调".to_string()));
+        }
+        
+        let (sender, receiver) = oneshot::channel();
+        self.host_requests.lock().unwrap().insert(request_id.clone(), sender);
+        let _guard = HostRequestGuard { shared: self, request_id };
+        self.send_event_to_rn(event);
+        
+        match tokio::time::timeout(self.request_timeout(), receiver).await {
+            Ok(result) => result.unwrap_or(Err(McpError::Disconnected)),
+            Err(_) => Err(McpError::Timeout),
+        }
+    }
+    
+    /// 处理服务器通知
+    fn handle_notification<T: Transport>(self: &Arc<Self>, transport: &Arc<T>, notification: JsonRpcNotification) {
+        match notification.method.as_str() {
+            "notifications/tools/list_changed" => {
+                // 重新获取工具列表后通知React Native
+                let shared = self.clone();
+                let transport = transport.clone();
+                tokio::spawn(async move {
+                    match shared.refresh_tools(&transport).await {
+                        Ok(tools) => shared.send_event_to_rn(McpEvent::ToolsChanged { tools }),
+                        Err(e) => log::warn!("刷新工具列表失败: {}", e),
+                    }
+                });
+            },
+            "notifications/resources/list_changed" => {
+                // 模板列表按需重新获取，资源列表刷新后通知React Native
+                self.resource_templates.lock().unwrap().take();
+                let shared = self.clone();
+                let transport = transport.clone();
+                tokio::spawn(async move {
+                    match shared.refresh_resources(&transport).await {
+                        Ok(resources) => shared.send_event_to_rn(McpEvent::ResourcesChanged { resources }),
+                        Err(e) => log::warn!("刷新资源列表失败: {}", e),
+                    }
+                });
+            },
+            "notifications/prompts/list_changed" => {
+                let shared = self.clone();
+                let transport = transport.clone();
+                tokio::spawn(async move {
+                    match shared.refresh_prompts(&transport).await {
+                        Ok(prompts) => shared.send_event_to_rn(McpEvent::PromptsChanged { prompts }),
+                        Err(e) => log::warn!("刷新提示词列表失败: {}", e),
+                    }
+                });
+            },
+            "notifications/progress" => {
+                match notification.params.map(serde_json::from_value::<ProgressParams>) {
+                    Some(Ok(params)) => self.handle_progress(params),
+                    _ => log::warn!("无效的进度通知"),
+                }
+            },
+            "notifications/message" => {
+                match notification.params.map(serde_json::from_value::<McpLogMessage>) {
+                    Some(Ok(record)) => self.handle_log_message(record),
+                    _ => log::warn!("无效的日志通知"),
+                }
+            },
+            "notifications/cancelled" => {
+                // 服务器取消了自己发起的请求
+                let id = notification.params.as_ref()
+                    .and_then(|params| params.get("requestId"))
+                    .and_then(|id| serde_json::from_value::<RequestId>(id.clone()).ok());
+                match id {
+                    Some(id) => {
+                        if let Some(cancel) = self.server_requests.lock().unwrap().remove(&id) {
+                            let _ = cancel.send(());
+                        }
+                    },
+                    None => log::warn!("cancelled通知缺少requestId参数"),
+                }
+            },
+            "notifications/resources/updated" => {
+                let uri = notification.params.as_ref()
+                    .and_then(|params| params.get("uri"))
+                    .and_then(|uri| uri.as_str());
+                match uri {
+                    Some(uri) => self.send_event_to_rn(McpEvent::ResourceUpdated { uri: uri.to_string() }),
+                    None => log::warn!("resources/updated通知缺少uri参数"),
+                }
+            },
+            _ => {
+                // 暂不处理的通知直接忽略
+            }
+        }
+    }
+    
+    /// 将服务器日志分发到各输出目标
+    fn handle_log_message(&self, record: McpLogMessage) {
+        let sinks = self.log_sinks.lock().unwrap().clone();
+        for sink in sinks {
+            match sink {
+                LogSink::RnEvent => self.send_event_to_rn(McpEvent::ServerLog {
+                    level: record.level,
+                    logger: record.logger.clone(),
+                    data: record.data.clone(),
+                }),
+                LogSink::LogCrate => {
+                    let level = match record.level {
+                        LoggingLevel::Debug => log::Level::Debug,
+                        LoggingLevel::Info | LoggingLevel::Notice => log::Level::Info,
+                        LoggingLevel::Warning => log::Level::Warn,
+                        _ => log::Level::Error,
+                    };
+                    let data = match &record.data {
+                        Value::String(text) => text.clone(),
+                        data => data.to_string(),
+                    };
+                    log::log!(
+                        target: "mcp::server",
+                        level,
+                        "[{}] {}",
+                        record.logger.as_deref().unwrap_or("server"),
+                        data
+                    );
+                },
+                LogSink::RingBuffer { capacity } => {
+                    let mut buffer = self.log_buffer.lock().unwrap();
+                    while buffer.len() >= capacity.max(1) {
+                        buffer.pop_front();
+                    }
+                    buffer.push_back(record.clone());
+                },
+            }
+        }
+    }
+    
+    /// 将进度通知交给对应请求的回调并通知React Native
+    fn handle_progress(&self, params: ProgressParams) {
+        let token = match &params.progress_token {
+            Value::String(token) => token.clone(),
+            token => token.to_string(),
+        };
+        
+        let (call_id, callback) = match self.progress_routes.lock().unwrap().get(&token) {
+            Some(route) => (route.call_id.clone(), route.callback.clone()),
+            // 请求已结束或令牌未知，忽略
+            None => return,
+        };
+        
+        if let Some(callback) = callback {
+            callback(&params);
+        }
+        
+        self.send_event_to_rn(McpEvent::Progress {
+            call_id,
+            progress: params.progress,
+            total: params.total,
+            message: params.message,
+        });
+    }
+    
+    /// 发送ping并返回往返延迟
+    async fn ping<T: Transport>(self: &Arc<Self>, transport: &Arc<T>, timeout: Duration) -> Result<Duration> {
+        let started = Instant::now();
+        match self.request(transport, Uuid::new_v4().to_string().into(), "ping", None, timeout).await {
+            // 服务器返回错误说明它仍在响应
+            Ok(_) | Err(McpError::ServerError { .. }) => {
+                let latency = started.elapsed();
+                let mut health = self.health.lock().unwrap();
+                health.latency = Some(latency);
+                health.consecutive_failures = 0;
+                Ok(latency)
+            }
+            Err(e) => {
+                if !matches!(e, McpError::Disconnected) {
+                    self.health.lock().unwrap().consecutive_failures += 1;
+                }
+                Err(e)
+            }
+        }
+    }
+    
+    /// 处理传输关闭：标记断开、结束待处理请求并通知React Native
+    fn handle_transport_closed(&self, error: Option<McpError>) {
+        let was_connected = self.connected.swap(false, Ordering::SeqCst);
+        self.fail_all_pending();
+        
+        if let Some(error) = error {
+            if !matches!(error, McpError::Disconnected) {
+                self.send_event_to_rn(McpEvent::Error { 
+                    code: "transport_error".to_string(), 
+                    message: error.to_string(),
+                });
+            }
+        }
+        
+        if was_connected {
+            self.send_event_to_rn(McpEvent::ConnectionState { 
+                connected: false, 
+                server_name: None,
+            });
+        }
+    }
+}
+
+/// 等待响应期间的清理守卫
+///
+/// 调用方在收到响应前丢弃future时移除待处理项，请求已发出时还会通知服务器取消请求
+struct PendingGuard<T: Transport> {
+    shared: Arc<ClientShared>,
+    transport: Arc<T>,
+    /// 仍在等待的请求ID，完成或已处理后为None
+    id: Option<RequestId>,
+    /// 请求已发出且允许取消
+    cancellable: bool,
+}
+
+impl<T: Transport> PendingGuard<T> {
+    /// 请求已结束，不再需要清理
+    fn disarm(&mut self) -> Option<RequestId> {
+        self.id.take()
+    }
+}
+
+impl<T: Transport> Drop for PendingGuard<T> {
+    fn drop(&mut self) {
+        let id = match self.id.take() {
+            Some(id) => id,
+            None => return,
+        };
+        if self.shared.take_pending(&id).is_none() || !self.cancellable {
+            return;
+        }
+        
+        // Drop中无法等待，在运行时中异步发送取消通知
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let shared = self.shared.clone();
+            let transport = self.transport.clone();
+            handle.spawn(async move {
+                let _ = shared.send_cancelled(&*transport, &id, "调用方已放弃请求").await;
+            });
+        }
+    }
+}
+
+/// 持续读取传输中的消息直到连接关闭，返回导致关闭的错误
+async fn run_message_loop<T: Transport>(shared: Arc<ClientShared>, transport: Arc<T>) -> Option<McpError> {
+    // 健康检查可以直接结束消息循环，不依赖传输关闭后receive能否返回
+    let (lost_tx, mut lost_rx) = oneshot::channel();
+    *shared.connection_lost.lock().unwrap() = Some(lost_tx);
+    
+    loop {
+        let received = tokio::select! {
+            received = transport.receive() => received,
+            Ok(()) = &mut lost_rx => return None,
+        };
+        
+        match received {
+            Ok(Some(frame)) => match decode_frame(&frame) {
+                Ok((format, message)) => {
+                    // 线路格式只按握手响应确定，之后的消息不会改变它
+                    if !shared.is_connected() && is_handshake_response(&message) {
+                        *shared.handshake_format.lock().unwrap() = Some(format);
+                    }
+                    shared.dispatch(&transport, message);
+                },
+                Err(e) => {
+                    log::warn!("解析消息失败: {}", e);
+                    reply_decode_error(&shared, &transport, &frame);
+                }
+            },
+            Ok(None) => return None,
+            Err(e) => return Some(e),
+        }
+    }
+}
+
+/// 无法解码的消息帧对应的错误响应
+///
+/// 无法解析的JSON回复`PARSE_ERROR`，带id但格式错误的请求回复`INVALID_REQUEST`；
+/// 格式错误的响应和通知不回复，避免双方互相回复错误
+fn decode_error_response(frame: &str) -> Option<JsonRpcResponse> {
+    let value: Value = match serde_json::from_str(frame) {
+        Ok(value) => value,
+        Err(e) => {
+            return Some(JsonRpcResponse::failure(
+                None,
+                JsonRpcError::new(error_codes::PARSE_ERROR, &format!("无法解析消息: {}", e)),
+            ));
+        }
+    };
+    
+    let object = value.as_object()?;
+    if !object.contains_key("jsonrpc") || !object.contains_key("method") {
+        return None;
+    }
+    // id本身无效时按协议以null回复
+    let id = serde_json::from_value(object.get("id")?.clone()).ok();
+    Some(JsonRpcResponse::failure(id, JsonRpcError::new(error_codes::INVALID_REQUEST, "无效的请求")))
+}
+
+/// 向服务器回复无法解码的消息帧，只在JSON-RPC格式下回复
+fn reply_decode_error<T: Transport>(shared: &Arc<ClientShared>, transport: &Arc<T>, frame: &str) {
+    if shared.wire_format() != WireFormat::JsonRpc {
+        return;
+    }
+    let response = match decode_error_response(frame) {
+        Some(response) => response,
+        None => return,
+    };
+    
+    // 在后台发送，不阻塞消息循环
+    let shared = shared.clone();
+    let transport = transport.clone();
+    tokio::spawn(async move {
+        if let Err(e) = shared.send(&*transport, &JsonRpcMessage::Response(response)).await {
+            log::warn!("回复无效消息失败: {}", e);
+        }
+    });
+}
+
+/// 判断消息是否为initialize请求的响应
+fn is_handshake_response(message: &JsonRpcMessage) -> bool {
+    matches!(
+        message,
+        JsonRpcMessage::Response(response) if response.id == Some(RequestId::from(LEGACY_HANDSHAKE_ID))
+    )
+}
+
+/// 定期发送ping，连续失败达到阈值时结束消息循环并关闭当前传输
+///
+/// 消息循环结束后由连接监督任务决定重连或标记断开；重连期间跳过检查
+async fn run_health_monitor<T: Transport>(shared: Arc<ClientShared>, connection: Arc<Connection<T>>, config: HealthCheckConfig) {
+    let mut ticker = tokio::time::interval(config.interval);
+    // 第一次tick立即返回，跳过
+    ticker.tick().await;
+    
+    loop {
+        ticker.tick().await;
+        if !shared.is_connected() || shared.wire_format() != WireFormat::JsonRpc {
+            continue;
+        }
+        let transport = match connection.transport() {
+            Some(transport) => transport,
+            None => return,
+        };
+        
+        match shared.ping(&transport, config.timeout).await {
+            Ok(_) | Err(McpError::Disconnected) => {}
+            Err(_) => {
+                let failures = shared.health.lock().unwrap().consecutive_failures;
+                if failures >= config.failure_threshold.max(1) {
+                    *shared.close_reason.lock().unwrap() = Some(McpError::ConnectionError(
+                        format!("服务器连续{}次未响应ping", failures),
+                    ));
+                    *shared.health.lock().unwrap() = HealthState::default();
+                    // 先结束消息循环再关闭传输，对端无响应时关闭可能无法唤醒receive
+                    if let Some(lost) = shared.connection_lost.lock().unwrap().take() {
+                        let _ = lost.send(());
+                    }
+                    let _ = transport.close().await;
+                }
+            }
+        }
+    }
+}
+
+/// 建立新传输的连接器，用于自动重连
+type Connector<T> = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<T>> + Send>> + Send + Sync>;
+
+/// 消息循环的future，重连时在握手期间和之后持续使用同一个
+type MessageLoop = Pin<Box<dyn Future<Output = Option<McpError>> + Send>>;
+
+/// 一次connect建立的连接，在自动重连时替换其中的传输
+struct Connection<T: Transport> {
+    /// 当前使用的传输
+    transport: Mutex<Option<Arc<T>>>,
+    /// 建立新传输的连接器，为None时不能自动重连
+    connector: Option<Connector<T>>,
+    /// 握手时发送的客户端信息
+    client_info: McpImplementation,
+    /// 握手时声明的客户端能力
+    client_capabilities: ClientCapabilities,
+}
+
+impl<T: Transport> Connection<T> {
+    fn transport(&self) -> Option<Arc<T>> {
+        self.transport.lock().unwrap().clone()
+    }
+}
+
+/// 执行initialize握手并发送initialized通知
+async fn handshake<T: Transport>(
+    shared: &Arc<ClientShared>,
+    transport: &Arc<T>,
+    client_info: &McpImplementation,
+    client_capabilities: &ClientCapabilities,
+) -> Result<()> {
+    // 握手请求按宿主选择的格式发送
+    let preferred_format = *shared.preferred_format.lock().unwrap();
+    *shared.wire_format.lock().unwrap() = preferred_format;
+    shared.handshake_format.lock().unwrap().take();
+    
+    // 旧版标签格式的服务器只理解旧版协议版本
+    let requested_version = match preferred_format {
+        WireFormat::JsonRpc => LATEST_PROTOCOL_VERSION,
+        WireFormat::Tagged => LEGACY_PROTOCOL_VERSION,
+    };
+    
+    // 设置过根目录时声明roots能力
+    let mut capabilities = client_capabilities.clone();
+    if capabilities.roots.is_none() && shared.roots.lock().unwrap().is_some() {
+        capabilities.roots = Some(ListChangedCapability { list_changed: true });
+    }
+    
+    let params = InitializeParams {
+        protocol_version: requested_version.to_string(),
+        capabilities: capabilities.clone(),
+        client_info: client_info.clone(),
+    };
+    let params = serde_json::to_value(params)
+        .map_err(|e| McpError::ProtocolError(format!("序列化握手参数失败: {}", e)))?;
+    
+    let result = shared.request(
+        transport,
+        LEGACY_HANDSHAKE_ID.into(),
+        "initialize",
+        Some(params),
+        shared.request_timeout(),
+    ).await?;
+    let result: InitializeResult = serde_json::from_value(result)
+        .map_err(|e| McpError::ProtocolError(format!("解析握手响应失败: {}", e)))?;
+    
+    // 按服务器握手响应的格式确定本次连接的线路格式
+    if let Some(format) = shared.handshake_format.lock().unwrap().take() {
+        *shared.wire_format.lock().unwrap() = format;
+    }
+    
+    // 服务器可以返回不同的版本，但必须是客户端支持的版本
+    if !SUPPORTED_PROTOCOL_VERSIONS.contains(&result.protocol_version.as_str()) {
+        return Err(McpError::IncompatibleVersion {
+            requested: requested_version.to_string(),
+            received: result.protocol_version,
+        });
+    }
+    
+    let server_name = result.server_info.as_ref().map(|info| info.name.clone());
+    *shared.session.lock().unwrap() = SessionInfo {
+        server_info: result.server_info,
+        server_capabilities: Some(result.capabilities),
+        protocol_version: Some(result.protocol_version),
+        client_capabilities: Some(capabilities),
+    };
+    *shared.health.lock().unwrap() = HealthState::default();
+    
+    shared.send(&**transport, &JsonRpcMessage::Notification(JsonRpcNotification::new(
+        "notifications/initialized",
+        None,
+    ))).await?;
+    
+    // 发送initialized通知后握手才算完成，此前不允许发送其他请求
+    shared.connected.store(true, Ordering::SeqCst);
+    
+    // 通知React Native连接状态变更
+    shared.send_event_to_rn(McpEvent::ConnectionState { 
+        connected: true, 
+        server_name,
+    });
+    
+    Ok(())
+}
+
+/// 重连后恢复会话：重新握手、订阅资源、按策略重发请求并刷新工具列表
+async fn restore_session<T: Transport>(
+    shared: &Arc<ClientShared>,
+    connection: &Connection<T>,
+    transport: &Arc<T>,
+    in_flight: InFlightPolicy,
+) -> Result<()> {
+    handshake(shared, transport, &connection.client_info, &connection.client_capabilities).await?;
+    
+    // 服务器的列表可能已变化，缓存按需重新获取
+    shared.clear_caches();
+    
+    if in_flight == InFlightPolicy::Replay {
+        shared.replay_pending(&**transport).await?;
+    }
+    
+    let uris: Vec<String> = shared.subscriptions.lock().unwrap().iter().cloned().collect();
+    for uri in uris {
+        let result = shared.request(
+            transport,
+            Uuid::new_v4().to_string().into(),
+            "resources/subscribe",
+            Some(json!({ "uri": uri })),
+            shared.request_timeout(),
+        ).await;
+        if let Err(e) = result {
+            // 服务器不再接受该订阅，移除以免之后误报为已订阅
+            log::warn!("重新订阅资源{}失败: {}", uri, e);
+            shared.subscriptions.lock().unwrap().remove(&uri);
+        }
+    }
+    
+    // 旧版标签格式不支持tools/list，使用握手时返回的工具列表
+    match shared.wire_format() {
+        WireFormat::JsonRpc => {
+            // 未声明tools能力的服务器不支持tools/list；刷新失败不影响会话恢复，之后按需重新获取
+            let supports_tools = shared.server_capabilities().is_some_and(|capabilities| capabilities.tools.is_some());
+            if supports_tools {
+                match shared.refresh_tools(transport).await {
+                    Ok(tools) => shared.send_event_to_rn(McpEvent::ToolsChanged { tools }),
+                    Err(e) => log::warn!("重连后刷新工具列表失败: {}", e),
+                }
+            }
+        }
+        WireFormat::Tagged => {
+            let tools = shared.server_info().map(|info| info.tools).unwrap_or_default();
+            *shared.tools.lock().unwrap() = Some(tools.clone());
+            shared.send_event_to_rn(McpEvent::ToolsChanged { tools });
+        }
+    }
+    
+    Ok(())
+}
+
+/// 按退避策略重连，成功时返回新传输的消息循环
+async fn reconnect<T: Transport>(
+    shared: &Arc<ClientShared>,
+    connection: &Connection<T>,
+    connector: &Connector<T>,
+    config: &ReconnectConfig,
+) -> Option<MessageLoop> {
+    let mut delay_ms = config.retry.initial_delay_ms;
+    
+    for attempt in 1..=config.retry.max_retries {
+        shared.send_event_to_rn(McpEvent::Reconnecting { attempt, delay_ms });
+        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+        delay_ms = config.retry.next_delay_ms(delay_ms);
+        
+        let transport = match connector().await {
+            Ok(transport) => Arc::new(transport),
+            Err(e) => {
+                log::debug!("第{}次重连失败: {}", attempt, e);
+                continue;
+            }
+        };
+        *connection.transport.lock().unwrap() = Some(transport.clone());
+        
+        // 握手期间需要消息循环接收响应
+        let mut message_loop: MessageLoop = Box::pin(run_message_loop(shared.clone(), transport.clone()));
+        let restored = tokio::select! {
+            result = restore_session(shared, connection, &transport, config.in_flight) => result,
+            error = &mut message_loop => Err(error.unwrap_or(McpError::Disconnected)),
+        };
+        
+        match restored {
+            Ok(()) => {
+                shared.send_event_to_rn(McpEvent::Reconnected {
+                    attempt,
+                    server_name: shared.server_info().map(|info| info.name),
+                });
+                return Some(message_loop);
+            }
+            Err(e) => {
+                log::warn!("第{}次重连后恢复会话失败: {}", attempt, e);
+                // 握手可能已成功并通知了已连接
+                if shared.connected.swap(false, Ordering::SeqCst) {
+                    shared.send_event_to_rn(McpEvent::ConnectionState {
+                        connected: false,
+                        server_name: None,
+                    });
+                }
+                let _ = transport.close().await;
+            }
+        }
+    }
+    
+    None
+}
+
+/// 连接监督任务：运行消息循环，连接意外断开时按配置自动重连
+async fn run_connection<T: Transport>(shared: Arc<ClientShared>, connection: Arc<Connection<T>>) {
+    let transport = match connection.transport() {
+        Some(transport) => transport,
+        None => return,
+    };
+    let mut message_loop: MessageLoop = Box::pin(run_message_loop(shared.clone(), transport));
+    
+    loop {
+        let error = message_loop.await;
+        let error = shared.close_reason.lock().unwrap().take().or(error);
+        
+        // 只有握手成功后的意外断开才重连
+        let config = shared.reconnect.lock().unwrap().clone();
+        let (connector, config) = match (&connection.connector, config) {
+            (Some(connector), Some(config)) if shared.is_connected() => (connector, config),
+            _ => {
+                shared.handle_transport_closed(error);
+                return;
+            }
+        };
+        
+        // 重连前先通知连接已断开，重连成功后握手会再次通知已连接
+        shared.connected.store(false, Ordering::SeqCst);
+        shared.send_event_to_rn(McpEvent::ConnectionState {
+            connected: false,
+            server_name: None,
+        });
+        shared.fail_host_requests();
+        if config.in_flight == InFlightPolicy::Fail {
+            shared.fail_all_pending();
+        }
+        if let Some(error) = &error {
+            log::warn!("连接已断开，准备重连: {}", error);
+        }
+        // 释放旧传输占用的资源 (如stdio服务器进程)
+        if let Some(transport) = connection.transport() {
+            let _ = transport.close().await;
+        }
+        
+        match reconnect(&shared, &connection, connector, &config).await {
+            Some(next_loop) => message_loop = next_loop,
+            None => {
+                shared.fail_all_pending();
+                shared.send_event_to_rn(McpEvent::Error {
+                    code: "reconnect_failed".to_string(),
+                    message: format!("重连{}次后仍未成功", config.retry.max_retries),
+                });
+                return;
+            }
+        }
+    }
+}
+
+// ===== MCP客户端 =====
+
+/// MCP客户端实现
+///
+/// 客户端对具体传输方式泛型，默认使用动态分发的传输以便按服务器地址选择
+pub struct McpClient<T: Transport = Box<dyn Transport>> {
+    /// 与消息循环共享的状态
+    shared: Arc<ClientShared>,
+    /// 客户端信息
+    client_info: McpImplementation,
+    /// 客户端能力，注册服务器请求处理器时会随之更新
+    client_capabilities: Mutex<ClientCapabilities>,
+    /// 与服务器的连接 (连接后可用)
+    connection: Mutex<Option<Arc<Connection<T>>>>,
+    /// 后台消息读取任务，连接意外断开时负责重连
+    reader_task: Mutex<Option<JoinHandle<()>>>,
+    /// 串行化连接和断开，两者都只需共享借用，可与其他调用并发
+    lifecycle: tokio::sync::Mutex<()>,
+    /// 健康检查配置，为None时不发送ping
+    health_check: Mutex<Option<HealthCheckConfig>>,
+    /// 后台健康检查任务
+    health_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl<T: Transport> McpClient<T> {
+    /// 创建新的MCP客户端
+    pub fn new() -> Self {
+        McpClient {
+            shared: Arc::new(ClientShared::new()),
+            client_info: McpImplementation {
+                name: "yu-mcp-client".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            client_capabilities: Mutex::new(ClientCapabilities::default()),
+            connection: Mutex::new(None),
+            reader_task: Mutex::new(None),
+            lifecycle: tokio::sync::Mutex::new(()),
+            health_check: Mutex::new(Some(HealthCheckConfig::default())),
+            health_task: Mutex::new(None),
+        }
+    }
+    
+    /// 设置首选线路格式，在下次握手时生效
+    ///
+    /// 连接旧版标签格式的服务器时应设置为`WireFormat::Tagged`；
+    /// 握手时按服务器响应的格式确定连接的线路格式，连接期间不再改变
+    pub fn set_wire_format(&self, format: WireFormat) {
+        *self.shared.preferred_format.lock().unwrap() = format;
+        if !self.is_connected() {
+            *self.shared.wire_format.lock().unwrap() = format;
+        }
+    }
+    
+    /// 获取当前连接的线路格式
+    pub fn wire_format(&self) -> WireFormat {
+        self.shared.wire_format()
+    }
+    
+    /// 设置握手时发送的客户端信息
+    pub fn set_client_info(&mut self, client_info: McpImplementation) {
+        self.client_info = client_info;
+    }
+    
+    /// 设置握手时声明的客户端能力
+    pub fn set_client_capabilities(&self, capabilities: ClientCapabilities) {
+        *self.client_capabilities.lock().unwrap() = capabilities;
+    }
+    
+    /// 设置默认请求超时时间
+    pub fn set_request_timeout(&self, timeout: Duration) {
+        *self.shared.request_timeout.lock().unwrap() = timeout;
+    }
+    
+    /// 注册服务器请求处理器，同一方法重复注册时替换旧处理器
+    ///
+    /// 处理器返回的错误会映射为JSON-RPC错误回复给服务器
+    pub fn set_request_handler<F, Fut>(&self, method: &str, handler: F)
+    where
+        F: Fn(Option<Value>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value>> + Send + 'static,
+    {
+        let handler: ServerRequestHandler = Arc::new(move |params| Box::pin(handler(params)));
+        self.shared.request_handlers.lock().unwrap().insert(method.to_string(), handler);
+        
+        // 处理采样和elicitation请求需要在握手时声明对应能力
+        let mut capabilities = self.client_capabilities.lock().unwrap();
+        match method {
+            "sampling/createMessage" if capabilities.sampling.is_none() => {
+                capabilities.sampling = Some(json!({}));
+            }
+            "elicitation/create" if capabilities.elicitation.is_none() => {
+                capabilities.elicitation = Some(json!({}));
+            }
+            _ => {}
+        }
+    }
+    
+    /// 移除服务器请求处理器
+    pub fn remove_request_handler(&self, method: &str) {
+        self.shared.request_handlers.lock().unwrap().remove(method);
+        let mut capabilities = self.client_capabilities.lock().unwrap();
+        match method {
+            "sampling/createMessage" => capabilities.sampling = None,
+            "elicitation/create" => capabilities.elicitation = None,
+            _ => {}
+        }
+    }
+    
+    /// 注册采样请求 (sampling/createMessage) 处理器
+    pub fn set_sampling_handler<F, Fut>(&self, handler: F)
+    where
+        F: Fn(CreateMessageParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<CreateMessageResult>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        self.set_request_handler("sampling/createMessage", move |params| {
+            let handler = handler.clone();
+            async move {
+                let params: CreateMessageParams = serde_json::from_value(params.unwrap_or(Value::Null))
+                    .map_err(|e| McpError::ProtocolError(format!("无效的采样请求参数: {}", e)))?;
+                let result = handler(params).await?;
+                serde_json::to_value(result)
+                    .map_err(|e| McpError::InternalError(format!("序列化采样结果失败: {}", e)))
+            }
+        });
+    }
+    
+    /// 注册elicitation请求处理器
+    ///
+    /// 不符合协议限制的schema会直接以参数错误回复服务器；处理器返回accept时内容需通过schema校验
+    pub fn set_elicitation_handler<F, Fut>(&self, handler: F)
+    where
+        F: Fn(ElicitRequestParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ElicitResult>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        self.set_request_handler("elicitation/create", move |params| {
+            let handler = handler.clone();
+            async move {
+                let params = parse_elicit_params(params)?;
+                let requested_schema = params.requested_schema.clone();
+                let result = check_elicit_result(&requested_schema, handler(params).await?)?;
+                serde_json::to_value(result)
+                    .map_err(|e| McpError::InternalError(format!("序列化elicitation结果失败: {}", e)))
+            }
+        });
+    }
+    
+    /// 将指定方法的服务器请求转发给React Native处理
+    ///
+    /// React Native收到`McpEvent::ServerRequest`事件后，需调用`respond_to_server_request`回复；
+    /// `elicitation/create`请求以`McpEvent::Elicitation`事件转发，需调用`respond_to_elicitation`回复
+    pub fn forward_request_to_rn(&self, method: &str) {
+        if method == "elicitation/create" {
+            let shared = Arc::downgrade(&self.shared);
+            self.set_request_handler(method, move |params| {
+                let shared = shared.clone();
+                async move {
+                    let shared = shared.upgrade().ok_or(McpError::Disconnected)?;
+                    shared.forward_elicitation_to_rn(parse_elicit_params(params)?).await
+                }
+            });
+            return;
+        }
+        
+        // 处理器保存在共享状态中，使用弱引用避免循环引用
+        let shared = Arc::downgrade(&self.shared);
+        let method_name = method.to_string();
+        self.set_request_handler(method, move |params| {
+            let shared = shared.clone();
+            let method = method_name.clone();
+            async move {
+                let shared = shared.upgrade().ok_or(McpError::Disconnected)?;
+                shared.forward_to_rn(&method, params).await
+            }
+        });
+    }
+    
+    /// 回复由React Native处理的服务器请求
+    ///
+    /// 返回false表示没有找到对应的请求 (可能已超时或连接已断开)
+    pub fn respond_to_server_request(&self, request_id: &str, result: Result<Value>) -> bool {
+        match self.shared.host_requests.lock().unwrap().remove(request_id) {
+            Some(sender) => sender.send(result).is_ok(),
+            None => false,
+        }
+    }
+    
+    /// 回复转发给React Native的elicitation请求
+    ///
+    /// accept时内容会按请求的schema校验，校验失败返回`McpError::InvalidContent`且请求保持等待，
+    /// 可修正后重新提交；返回false表示没有找到对应的请求
+    pub fn respond_to_elicitation(&self, request_id: &str, result: ElicitResult) -> Result<bool> {
+        let requested_schema = match self.shared.elicitations.lock().unwrap().get(request_id) {
+            Some(schema) => schema.clone(),
+            None => return Ok(false),
+        };
+        
+        let result = check_elicit_result(&requested_schema, result)?;
+        let result = serde_json::to_value(result)
+            .map_err(|e| McpError::InternalError(format!("序列化elicitation结果失败: {}", e)))?;
+        
+        self.shared.elicitations.lock().unwrap().remove(request_id);
+        Ok(self.respond_to_server_request(request_id, Ok(result)))
+    }
+    
+    /// 设置公开给服务器的根目录
+    ///
+    /// 首次设置应在连接前进行，以便握手时声明roots能力；
+    /// 连接后更新时，若握手时声明了roots能力则向服务器发送`notifications/roots/list_changed`
+    pub async fn set_roots(&self, roots: Vec<McpRoot>) -> Result<()> {
+        if let Some(root) = roots.iter().find(|root| !root.uri.starts_with("file://")) {
+            return Err(McpError::ProtocolError(format!("根目录URI必须以file://开头: {}", root.uri)));
+        }
+        
+        let changed = {
+            let mut current = self.shared.roots.lock().unwrap();
+            let changed = current.as_ref() != Some(&roots);
+            *current = Some(roots);
+            changed
+        };
+        
+        // 未声明roots能力的连接上服务器不会处理该通知
+        let advertised = self.shared.session.lock().unwrap().client_capabilities.as_ref()
+            .and_then(|capabilities| capabilities.roots.as_ref())
+            .is_some_and(|roots| roots.list_changed);
+        
+        if changed && advertised && self.is_connected() && self.wire_format() == WireFormat::JsonRpc {
+            self.send_message(JsonRpcMessage::Notification(JsonRpcNotification::new(
+                "notifications/roots/list_changed",
+                None,
+            ))).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 当前公开给服务器的根目录
+    pub fn roots(&self) -> Vec<McpRoot> {
+        self.shared.roots.lock().unwrap().clone().unwrap_or_default()
+    }
+    
+    /// 设置服务器日志的输出目标 (默认只发送给React Native)
+    pub fn set_log_sinks(&self, sinks: Vec<LogSink>) {
+        // 不再使用环形缓冲区时释放已保存的记录
+        if !sinks.iter().any(|sink| matches!(sink, LogSink::RingBuffer { .. })) {
+            self.shared.log_buffer.lock().unwrap().clear();
+        }
+        *self.shared.log_sinks.lock().unwrap() = sinks;
+    }
+    
+    /// 环形缓冲区中保存的服务器日志 (从旧到新)
+    pub fn recent_server_logs(&self) -> Vec<McpLogMessage> {
+        self.shared.log_buffer.lock().unwrap().iter().cloned().collect()
+    }
+    
+    /// 设置服务器发送日志的最低级别
+    pub async fn set_server_log_level(&self, level: LoggingLevel) -> Result<()> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        let supported = self.wire_format() == WireFormat::JsonRpc
+            && self.shared.server_capabilities().is_some_and(|caps| caps.logging.is_some());
+        if !supported {
+            return Err(McpError::ProtocolError("服务器不支持日志级别设置".to_string()));
+        }
+        
+        self.request(
+            Uuid::new_v4().to_string().into(),
+            "logging/setLevel",
+            Some(json!({ "level": level })),
+        ).await?;
+        Ok(())
+    }
+    
+    /// 设置连接健康检查，为None时关闭
+    ///
+    /// 已连接时立即按新配置重启检查任务，必须在tokio运行时中调用
+    pub fn set_health_check(&self, config: Option<HealthCheckConfig>) {
+        *self.health_check.lock().unwrap() = config;
+        self.spawn_health_monitor();
+    }
+    
+    /// 向服务器发送ping，返回往返延迟
+    pub async fn ping(&self) -> Result<Duration> {
+        let transport = self.transport()?;
+        self.shared.ping(&transport, self.shared.request_timeout()).await
+    }
+    
+    /// 获取连接健康状态
+    pub fn connection_health(&self) -> ConnectionHealth {
+        let health = self.shared.health.lock().unwrap();
+        ConnectionHealth {
+            connected: self.is_connected(),
+            latency_ms: health.latency.map(|latency| latency.as_millis() as u64),
+            consecutive_failures: health.consecutive_failures,
+        }
+    }
+    
+    /// 设置自动重连，为None时关闭 (默认开启)
+    ///
+    /// 只有通过`connect_with`或`connect_url`建立的连接才能自动重连
+    pub fn set_reconnect(&self, config: Option<ReconnectConfig>) {
+        *self.shared.reconnect.lock().unwrap() = config;
+    }
+    
+    /// 设置React Native回调
+    pub fn set_rn_callback(&self, callback: RNCallback) {
+        *self.shared.rn_callback.lock().unwrap() = Some(callback);
+    }
+    
+    /// 处理来自React Native的输入消息
+    ///
+    /// 服务器请求会在后台任务中回复，必须在tokio运行时上下文中调用
+    /// RN的输入可以使用任一格式，不影响与服务器连接的线路格式
+    pub fn handle_input_from_rn(&self, message: &str) -> Result<()> {
+        let (_, mcp_message) = decode_frame(message)
+            .map_err(|e| McpError::ProtocolError(format!("解析消息失败: {}", e)))?;
+        
+        self.handle_incoming_message(mcp_message)
+    }
+    
+    /// 处理传入的MCP消息
+    fn handle_incoming_message(&self, message: JsonRpcMessage) -> Result<()> {
+        let transport = self.transport()?;
+        self.shared.dispatch(&transport, message);
+        
+        Ok(())
+    }
+    
+    /// 向React Native发送事件
+    fn send_event_to_rn(&self, event: McpEvent) {
+        self.shared.send_event_to_rn(event);
+    }
+    
+    /// 通过给定的传输连接到MCP服务器
+    ///
+    /// 没有连接器无法建立新传输，连接断开后不会自动重连；需要重连时使用`connect_with`
+    pub async fn connect(&self, transport: T) -> Result<()> {
+        self.start(Arc::new(transport), None).await
+    }
+    
+    /// 使用连接器连接到MCP服务器
+    ///
+    /// 连接意外断开时按重连配置再次调用连接器建立新传输，并恢复会话
+    pub async fn connect_with<F, Fut>(&self, connector: F) -> Result<()>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+    {
+        let connector: Connector<T> = Arc::new(move || Box::pin(connector()));
+        let transport = connector().await?;
+        self.start(Arc::new(transport), Some(connector)).await
+    }
+    
+    /// 启动连接任务并执行握手
+    async fn start(&self, transport: Arc<T>, connector: Option<Connector<T>>) -> Result<()> {
+        let _lifecycle = self.lifecycle.lock().await;
+        
+        // 重新连接时停止旧的后台任务
+        self.stop_tasks();
+        
+        let connection = Arc::new(Connection {
+            transport: Mutex::new(Some(transport.clone())),
+            connector,
+            client_info: self.client_info.clone(),
+            client_capabilities: self.client_capabilities.lock().unwrap().clone(),
+        });
+        *self.connection.lock().unwrap() = Some(connection.clone());
+        
+        // 启动消息处理循环，握手响应由它接收
+        *self.reader_task.lock().unwrap() = Some(tokio::spawn(run_connection(self.shared.clone(), connection.clone())));
+        
+        // 执行初始化握手，失败时停止消息循环并关闭传输，不保留半开的连接
+        if let Err(e) = handshake(&self.shared, &transport, &connection.client_info, &connection.client_capabilities).await {
+            self.stop_tasks();
+            self.connection.lock().unwrap().take();
+            *self.shared.session.lock().unwrap() = SessionInfo::default();
+            let _ = transport.close().await;
+            return Err(e);
+        }
+        
+        self.spawn_health_monitor();
+        Ok(())
+    }
+    
+    /// 启动健康检查任务
+    fn spawn_health_monitor(&self) {
+        let mut health_task = self.health_task.lock().unwrap();
+        if let Some(health_task) = health_task.take() {
+            health_task.abort();
+        }
+        *self.shared.health.lock().unwrap() = HealthState::default();
+        
+        let config = self.health_check.lock().unwrap().clone();
+        let connection = self.connection.lock().unwrap().clone();
+        if let (Some(config), Some(connection)) = (config, connection) {
+            *health_task = Some(tokio::spawn(run_health_monitor(
+                self.shared.clone(),
+                connection,
+                config,
+            )));
+        }
+    }
+    
+    /// 停止后台任务
+    fn stop_tasks(&self) {
+        if let Some(reader_task) = self.reader_task.lock().unwrap().take() {
+            reader_task.abort();
+        }
+        if let Some(health_task) = self.health_task.lock().unwrap().take() {
+            health_task.abort();
+        }
+    }
+    
+    /// 当前使用的传输
+    fn transport(&self) -> Result<Arc<T>> {
+        self.connection.lock().unwrap().as_ref()
+            .and_then(|connection| connection.transport())
+            .ok_or(McpError::Disconnected)
+    }
+    
+    /// 发送消息到服务器
+    async fn send_message(&self, message: JsonRpcMessage) -> Result<()> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        let transport = self.transport()?;
+        self.shared.send(&*transport, &message).await
+    }
+    
+    /// 发送请求并在默认超时时间内等待响应结果
+    async fn request(&self, id: RequestId, method: &str, params: Option<Value>) -> Result<Value> {
+        self.request_with_timeout(id, method, params, self.shared.request_timeout()).await
+    }
+    
+    /// 发送请求并在指定时间内等待响应结果
+    async fn request_with_timeout(
+        &self,
+        id: RequestId,
+        method: &str,
+        params: Option<Value>,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let transport = self.transport()?;
+        self.shared.request(&transport, id, method, params, timeout).await
+    }
+    
+    /// 取消正在进行的请求
+    ///
+    /// 等待中的调用会收到`McpError::Cancelled`；返回false表示没有找到对应的请求
+    pub async fn cancel(&self, request_id: &str, reason: Option<&str>) -> Result<bool> {
+        let id = RequestId::from(request_id);
+        let sender = match self.shared.take_pending(&id) {
+            Some(sender) => sender,
+            None => return Ok(false),
+        };
+        let _ = sender.send(Err(McpError::Cancelled));
+        
+        if let Ok(transport) = self.transport() {
+            self.shared.send_cancelled(&*transport, &id, reason.unwrap_or("客户端取消")).await?;
+        }
+        
+        Ok(true)
+    }
+    
+    /// 调用工具
+    pub async fn call_tool(&self, name: &str, parameters: HashMap<String, serde_json::Value>) -> Result<McpResponse> {
+        self.call_tool_with_options(name, parameters, RequestOptions::default()).await
+    }
+    
+    /// 使用指定选项调用工具
+    pub async fn call_tool_with_options(
+        &self,
+        name: &str,
+        parameters: HashMap<String, serde_json::Value>,
+        options: RequestOptions,
+    ) -> Result<McpResponse> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        self.validate_tool_arguments(name, &parameters)?;
+        
+        // 创建唯一ID
+        let call_id = options.request_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        
+        // 通知React Native有工具调用
+        self.send_event_to_rn(McpEvent::ToolCall { 
+            call_id: call_id.clone(), 
+            name: name.to_string(), 
+            parameters: parameters.clone() 
+        });
+        
+        // 以调用ID作为进度令牌，请求结束后移除路由
+        self.shared.progress_routes.lock().unwrap().insert(call_id.clone(), ProgressRoute {
+            call_id: call_id.clone(),
+            callback: options.progress,
+        });
+        let _progress_guard = ProgressGuard {
+            shared: self.shared.clone(),
+            token: call_id.clone(),
+        };
+        
+        let result = self.request_with_timeout(
+            call_id.clone().into(),
+            "tools/call",
+            Some(json!({
+                "name": name,
+                "arguments": parameters,
+                "_meta": { "progressToken": call_id },
+            })),
+            options.timeout.unwrap_or(self.shared.request_timeout()),
+        ).await?;
+        
+        serde_json::from_value::<McpResponse>(result)
+            .map_err(|e| McpError::ProtocolError(format!("收到非预期响应类型: {}", e)))
+    }
+    
+    /// 按工具的inputSchema校验参数
+    ///
+    /// 仅使用已缓存的工具定义，未知工具不做校验，交由服务器判断
+    fn validate_tool_arguments(&self, name: &str, parameters: &HashMap<String, serde_json::Value>) -> Result<()> {
+        let schema = {
+            let tools = self.shared.tools.lock().unwrap();
+            let cached = tools.as_ref().and_then(|tools| tools.iter().find(|tool| tool.name == name).cloned());
+            cached
+                .or_else(|| self.shared.server_info().and_then(|info| info.tools.into_iter().find(|tool| tool.name == name)))
+                .map(|tool| tool.parameters_schema)
+        };
+        
+        let schema = match schema {
+            Some(schema) => schema,
+            None => return Ok(()),
+        };
+        
+        let arguments = Value::Object(parameters.clone().into_iter().collect());
+        let violations = schema::validate(&schema, &arguments);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(McpError::InvalidArguments { tool: name.to_string(), violations })
+        }
+    }
+    
+    /// 获取服务器提供的工具列表
+    ///
+    /// 结果会被缓存，服务器发送`tools/list_changed`通知时自动刷新
+    pub async fn list_tools(&self) -> Result<Vec<McpTool>> {
+        if let Some(tools) = self.shared.tools.lock().unwrap().clone() {
+            return Ok(tools);
+        }
+        self.refresh_tools().await
+    }
+    
+    /// 忽略缓存，重新获取工具列表
+    pub async fn refresh_tools(&self) -> Result<Vec<McpTool>> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        // 旧版标签格式不支持tools/list，使用握手时返回的工具列表
+        if self.wire_format() == WireFormat::Tagged {
+            let tools = self.shared.server_info().map(|info| info.tools).unwrap_or_default();
+            *self.shared.tools.lock().unwrap() = Some(tools.clone());
+            return Ok(tools);
+        }
+        
+        let transport = self.transport()?;
+        self.shared.refresh_tools(&transport).await
+    }
+    
+    /// 获取服务器提供的资源列表
+    ///
+    /// 结果会被缓存，服务器发送`resources/list_changed`通知时自动刷新
+    pub async fn list_resources(&self) -> Result<Vec<McpResourceInfo>> {
+        if let Some(resources) = self.shared.resources.lock().unwrap().clone() {
+            return Ok(resources);
+        }
+        self.refresh_resources().await
+    }
+    
+    /// 忽略缓存，重新获取资源列表
+    pub async fn refresh_resources(&self) -> Result<Vec<McpResourceInfo>> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        // 服务器未声明资源能力 (或使用旧版协议) 时没有可列出的资源
+        if !self.supports_resources() {
+            *self.shared.resources.lock().unwrap() = Some(Vec::new());
+            return Ok(Vec::new());
+        }
+        
+        let transport = self.transport()?;
+        self.shared.refresh_resources(&transport).await
+    }
+    
+    /// 获取服务器提供的资源模板列表 (结果会被缓存)
+    pub async fn list_resource_templates(&self) -> Result<Vec<McpResourceTemplate>> {
+        if let Some(templates) = self.shared.resource_templates.lock().unwrap().clone() {
+            return Ok(templates);
+        }
+        self.refresh_resource_templates().await
+    }
+    
+    /// 忽略缓存，重新获取资源模板列表
+    pub async fn refresh_resource_templates(&self) -> Result<Vec<McpResourceTemplate>> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        if !self.supports_resources() {
+            *self.shared.resource_templates.lock().unwrap() = Some(Vec::new());
+            return Ok(Vec::new());
+        }
+        
+        let transport = self.transport()?;
+        self.shared.refresh_resource_templates(&transport).await
+    }
+    
+    /// 订阅资源，资源更新时会发出`McpEvent::ResourceUpdated`事件
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<()> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        let supported = self.wire_format() == WireFormat::JsonRpc
+            && self.shared.server_capabilities()
+                .and_then(|caps| caps.resources)
+                .is_some_and(|resources| resources.subscribe);
+        if !supported {
+            return Err(McpError::ProtocolError("服务器不支持资源订阅".to_string()));
+        }
+        
+        self.request(
+            Uuid::new_v4().to_string().into(),
+            "resources/subscribe",
+            Some(json!({ "uri": uri })),
+        ).await?;
+        
+        self.shared.subscriptions.lock().unwrap().insert(uri.to_string());
+        Ok(())
+    }
+    
+    /// 取消资源订阅
+    pub async fn unsubscribe_resource(&self, uri: &str) -> Result<()> {
+        if !self.shared.subscriptions.lock().unwrap().contains(uri) {
+            return Ok(());
+        }
+        
+        self.request(
+            Uuid::new_v4().to_string().into(),
+            "resources/unsubscribe",
+            Some(json!({ "uri": uri })),
+        ).await?;
+        
+        self.shared.subscriptions.lock().unwrap().remove(uri);
+        Ok(())
+    }
+    
+    /// 当前已订阅的资源URI
+    pub fn subscribed_resources(&self) -> Vec<String> {
+        self.shared.subscriptions.lock().unwrap().iter().cloned().collect()
+    }
+    
+    /// 获取服务器提供的提示词列表
+    ///
+    /// 结果会被缓存，服务器发送`prompts/list_changed`通知时自动刷新
+    pub async fn list_prompts(&self) -> Result<Vec<McpPrompt>> {
+        if let Some(prompts) = self.shared.prompts.lock().unwrap().clone() {
+            return Ok(prompts);
+        }
+        self.refresh_prompts().await
+    }
+    
+    /// 忽略缓存，重新获取提示词列表
+    pub async fn refresh_prompts(&self) -> Result<Vec<McpPrompt>> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        // 服务器未声明提示词能力 (或使用旧版协议) 时没有可列出的提示词
+        let supported = self.wire_format() == WireFormat::JsonRpc
+            && self.shared.server_capabilities().is_some_and(|caps| caps.prompts.is_some());
+        if !supported {
+            *self.shared.prompts.lock().unwrap() = Some(Vec::new());
+            return Ok(Vec::new());
+        }
+        
+        let transport = self.transport()?;
+        self.shared.refresh_prompts(&transport).await
+    }
+    
+    /// 获取提示词并用给定参数展开
+    pub async fn get_prompt(&self, name: &str, arguments: HashMap<String, String>) -> Result<GetPromptResult> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        let mut params = json!({ "name": name });
+        if !arguments.is_empty() {
+            params["arguments"] = json!(arguments);
+        }
+        
+        let result = self.request(Uuid::new_v4().to_string().into(), "prompts/get", Some(params)).await?;
+        serde_json::from_value::<GetPromptResult>(result)
+            .map_err(|e| McpError::ProtocolError(format!("收到非预期响应类型: {}", e)))
+    }
+    
+    /// 请求参数补全建议
+    ///
+    /// reference指定提示词或资源模板，partial_value为用户已输入的部分内容
+    pub async fn complete(
+        &self,
+        reference: CompletionReference,
+        argument_name: &str,
+        partial_value: &str,
+    ) -> Result<CompletionResult> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        if self.wire_format() == WireFormat::Tagged {
+            return Err(McpError::ProtocolError("旧版协议不支持参数补全".to_string()));
+        }
+        
+        let params = json!({
+            "ref": reference,
+            "argument": { "name": argument_name, "value": partial_value },
+        });
+        let result = self.request(Uuid::new_v4().to_string().into(), "completion/complete", Some(params)).await?;
+        
+        serde_json::from_value::<CompleteResult>(result)
+            .map(|result| result.completion)
+            .map_err(|e| McpError::ProtocolError(format!("收到非预期响应类型: {}", e)))
+    }
+    
+    /// 服务器是否支持资源列表
+    fn supports_resources(&self) -> bool {
+        self.wire_format() == WireFormat::JsonRpc
+            && self.shared.server_capabilities().is_some_and(|caps| caps.resources.is_some())
+    }
+    
+    /// 请求资源
+    pub async fn request_resource(&self, uri: &str) -> Result<McpResource> {
+        self.request_resource_with_options(uri, RequestOptions::default()).await
+    }
+    
+    /// 使用指定选项请求资源
+    pub async fn request_resource_with_options(&self, uri: &str, options: RequestOptions) -> Result<McpResource> {
+        if !self.is_connected() {
+            return Err(McpError::Disconnected);
+        }
+        
+        // 创建唯一ID
+        let request_id = options.request_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        
+        // 通知React Native有资源请求
+        self.send_event_to_rn(McpEvent::ResourceRequest { 
+            request_id: request_id.clone(), 
+            uri: uri.to_string() 
+        });
+        
+        let result = self.request_with_timeout(
+            request_id.into(),
+            "resources/read",
+            Some(json!({ "uri": uri })),
+            options.timeout.unwrap_or(self.shared.request_timeout()),
+        ).await?;
+        
+        serde_json::from_value::<McpResource>(result)
+            .map_err(|e| McpError::ProtocolError(format!("收到非预期响应类型: {}", e)))
+    }
+    
+    /// 断开连接
+    pub async fn disconnect(&self) -> Result<()> {
+        let _lifecycle = self.lifecycle.lock().await;
+        
+        self.shared.connected.store(false, Ordering::SeqCst);
+        *self.shared.session.lock().unwrap() = SessionInfo::default();
+        self.shared.clear_caches();
+        self.shared.subscriptions.lock().unwrap().clear();
+        
+        // 停止后台任务 (包括进行中的重连) 并关闭传输
+        self.stop_tasks();
+        self.shared.fail_all_pending();
+        let connection = self.connection.lock().unwrap().take();
+        if let Some(transport) = connection.and_then(|connection| connection.transport()) {
+            transport.close().await?;
+        }
+        
+        // 通知React Native连接已断开
+        self.send_event_to_rn(McpEvent::ConnectionState { 
+            connected: false, 
+            server_name: None,
+        });
+        
+        Ok(())
+    }
+    
+    /// 获取服务器信息
+    pub fn get_server_info(&self) -> Option<McpServerInfo> {
+        self.shared.server_info()
+    }
+    
+    /// 获取服务器能力
+    pub fn get_server_capabilities(&self) -> Option<ServerCapabilities> {
+        self.shared.server_capabilities()
+    }
+    
+    /// 获取协商后的协议版本
+    pub fn protocol_version(&self) -> Option<String> {
+        self.shared.session.lock().unwrap().protocol_version.clone()
+    }
+    
+    /// 检查是否已连接
+    pub fn is_connected(&self) -> bool {
+        self.shared.is_connected()
+    }
+}
+
+impl<T: Transport> Default for McpClient<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Transport> Drop for McpClient<T> {
+    fn drop(&mut self) {
+        self.stop_tasks();
+    }
+}
+
+impl McpClient<Box<dyn Transport>> {
+    /// 设置按地址连接时的附加选项 (请求头、旧版SSE事件流地址)，在下次连接时生效
+    pub fn set_connect_options(&self, options: ConnectOptions) {
+        *self.shared.connect_options.lock().unwrap() = options;
+    }
+    
+    /// 按服务器地址选择传输并连接，连接断开后会按相同地址自动重连
+    ///
+    /// 每次连接 (包括重连) 都使用`set_connect_options`设置的最新选项
+    pub async fn connect_url(&self, server_url: &str) -> Result<()> {
+        let server_url = server_url.to_string();
+        let shared = self.shared.clone();
+        self.connect_with(move || {
+            let server_url = server_url.clone();
+            let options = shared.connect_options.lock().unwrap().clone();
+            async move { transport::connect_url(&server_url, &options).await }
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::protocol::McpContent;
+    use crate::mcp::transport::mock::{answer_defaults, response, text_result, tool, MockServer, MockTransport};
+    use std::sync::atomic::AtomicUsize;
+    use tokio::sync::mpsc;
+
+    /// 收集发送给React Native的事件
+    fn capture_events(client: &McpClient<MockTransport>) -> mpsc::UnboundedReceiver<Value> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        client.set_rn_callback(RNCallback {
+            callback_id: "test".to_string(),
+            handler: Arc::new(move |json| {
+                let _ = tx.send(serde_json::from_str(&json).unwrap());
+            }),
+        });
+        rx
     }
-}
 
-mod futures {
-    pub mod executor {
-        pub fn block_on<F, T>(future: F) -> T 
-        where 
-            F: std::future::Future<Output = T> {
-            // 简化的实现，实际上应该使用真正的异步运行时
-            panic!("block_on未真正实现");
+    /// 等待指定变体的事件，跳过其他事件
+    async fn next_event(events: &mut mpsc::UnboundedReceiver<Value>, variant: &str) -> Value {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let event = events.recv().await.expect("事件通道已关闭");
+                if let Some(body) = event.get(variant) {
+                    return body.clone();
+                }
+            }
+        }).await.expect("等待事件超时")
+    }
+
+    #[tokio::test]
+    async fn rejects_incompatible_protocol_version() {
+        let server = MockServer::new(|_, request| match request["method"].as_str() {
+            Some("initialize") => vec![response(request, json!({
+                "protocolVersion": "1999-01-01",
+                "capabilities": {},
+                "serverInfo": { "name": "old", "version": "0.1" }
+            }))],
+            _ => Vec::new(),
+        });
+        let client: McpClient<MockTransport> = McpClient::new();
+
+        match client.connect(server.transport()).await {
+            Err(McpError::IncompatibleVersion { requested, received }) => {
+                assert_eq!(requested, LATEST_PROTOCOL_VERSION);
+                assert_eq!(received, "1999-01-01");
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
         }
+        assert!(!client.is_connected());
+        assert_eq!(server.methods(), ["initialize"]);
+    }
+
+    #[tokio::test]
+    async fn failed_initialize_does_not_send_initialized() {
+        let server = MockServer::new(|_, request| vec![json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": { "code": -32603, "message": "boom" }
+        })]);
+        let client: McpClient<MockTransport> = McpClient::new();
+
+        assert!(client.connect(server.transport()).await.is_err());
+        assert_eq!(server.methods(), ["initialize"]);
+    }
+
+    #[tokio::test]
+    async fn sends_initialized_after_successful_initialize() {
+        let server = MockServer::new(|_, request| answer_defaults(request));
+        let client: McpClient<MockTransport> = McpClient::new();
+
+        client.connect(server.transport()).await.unwrap();
+        assert!(client.is_connected());
+        assert_eq!(server.methods(), ["initialize", "notifications/initialized"]);
+        assert_eq!(client.get_server_info().map(|info| info.name).as_deref(), Some("mock"));
+    }
+
+    #[test]
+    fn unparseable_frame_gets_parse_error_with_null_id() {
+        let response = decode_error_response("{not json").unwrap();
+        assert_eq!(response.id, None);
+        assert_eq!(response.error.unwrap().code, error_codes::PARSE_ERROR);
+    }
+
+    #[test]
+    fn malformed_request_gets_invalid_request_with_its_id() {
+        let response = decode_error_response(r#"{"jsonrpc":"2.0","id":7,"method":42}"#).unwrap();
+        assert_eq!(response.id, Some(RequestId::Number(7)));
+        assert_eq!(response.error.unwrap().code, error_codes::INVALID_REQUEST);
+
+        let response = decode_error_response(r#"{"jsonrpc":"1.0","id":{},"method":"ping"}"#).unwrap();
+        assert_eq!(response.id, None);
+        assert_eq!(response.error.unwrap().code, error_codes::INVALID_REQUEST);
+    }
+
+    #[test]
+    fn malformed_responses_and_notifications_are_not_answered() {
+        assert!(decode_error_response(r#"{"jsonrpc":"2.0","method":42}"#).is_none());
+        assert!(decode_error_response(r#"{"jsonrpc":"2.0","id":1}"#).is_none());
+        assert!(decode_error_response(r#"{"unknown":true}"#).is_none());
+    }
+
+    #[tokio::test]
+    async fn timeout_removes_pending_request_and_sends_cancelled() {
+        let server = MockServer::new(|_, request| answer_defaults(request));
+        let client: McpClient<MockTransport> = McpClient::new();
+        client.connect(server.transport()).await.unwrap();
+
+        let options = RequestOptions {
+            request_id: Some("slow-call".to_string()),
+            timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let result = client.call_tool_with_options("slow", HashMap::new(), options).await;
+
+        assert!(matches!(result, Err(McpError::Timeout)));
+        assert!(client.shared.pending_responses.lock().unwrap().is_empty());
+        let cancelled = server.sent().into_iter()
+            .find(|message| message["method"] == "notifications/cancelled")
+            .expect("未发送取消通知");
+        assert_eq!(cancelled["params"]["requestId"], "slow-call");
+    }
+
+    #[tokio::test]
+    async fn list_tools_follows_cursor_pages() {
+        let server = MockServer::new(|_, request| match request["method"].as_str() {
+            Some("tools/list") => match request["params"]["cursor"].as_str() {
+                None => vec![response(request, json!({ "tools": [tool("a")], "nextCursor": "page-2" }))],
+                Some("page-2") => vec![response(request, json!({ "tools": [tool("b")] }))],
+                Some(other) => panic!("unexpected cursor {}", other),
+            },
+            _ => answer_defaults(request),
+        });
+        let client: McpClient<MockTransport> = McpClient::new();
+        client.connect(server.transport()).await.unwrap();
+
+        let tools = client.list_tools().await.unwrap();
+
+        let names: Vec<_> = tools.iter().map(|tool| tool.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(server.methods().iter().filter(|method| *method == "tools/list").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn list_tools_stops_at_page_limit() {
+        let server = MockServer::new(|_, request| match request["method"].as_str() {
+            Some("tools/list") => {
+                let page = request["params"]["cursor"].as_str().map_or(0, |cursor| cursor.parse::<usize>().unwrap());
+                vec![response(request, json!({ "tools": [], "nextCursor": (page + 1).to_string() }))]
+            }
+            _ => answer_defaults(request),
+        });
+        let client: McpClient<MockTransport> = McpClient::new();
+        client.connect(server.transport()).await.unwrap();
+
+        assert!(matches!(client.list_tools().await, Err(McpError::ProtocolError(_))));
+        assert_eq!(server.methods().iter().filter(|method| *method == "tools/list").count(), MAX_LIST_PAGES);
+    }
+
+    #[tokio::test]
+    async fn tools_list_changed_refreshes_cache_and_emits_event() {
+        let version = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let version = version.clone();
+            MockServer::new(move |_, request| match request["method"].as_str() {
+                Some("tools/list") => {
+                    let name = format!("v{}", version.load(Ordering::SeqCst));
+                    vec![response(request, json!({ "tools": [tool(&name)] }))]
+                }
+                _ => answer_defaults(request),
+            })
+        };
+        let client: McpClient<MockTransport> = McpClient::new();
+        let mut events = capture_events(&client);
+        client.connect(server.transport()).await.unwrap();
+        assert_eq!(client.list_tools().await.unwrap()[0].name, "v0");
+
+        version.store(1, Ordering::SeqCst);
+        server.push(json!({ "jsonrpc": "2.0", "method": "notifications/tools/list_changed" }));
+
+        let changed = next_event(&mut events, "ToolsChanged").await;
+        assert_eq!(changed["tools"][0]["name"], "v1");
+        assert_eq!(client.list_tools().await.unwrap()[0].name, "v1");
+    }
+
+    #[tokio::test]
+    async fn progress_is_routed_to_the_matching_call() {
+        let server = MockServer::new(|_, request| match request["method"].as_str() {
+            Some("tools/call") => {
+                let name = request["params"]["name"].clone();
+                vec![
+                    json!({
+                        "jsonrpc": "2.0",
+                        "method": "notifications/progress",
+                        "params": {
+                            "progressToken": request["params"]["_meta"]["progressToken"],
+                            "progress": 1,
+                            "total": 2,
+                            "message": name,
+                        }
+                    }),
+                    response(request, text_result("done")),
+                ]
+            }
+            _ => answer_defaults(request),
+        });
+        let client: McpClient<MockTransport> = McpClient::new();
+        let mut events = capture_events(&client);
+        client.connect(server.transport()).await.unwrap();
+
+        let call = |id: &'static str| {
+            let seen = Arc::new(Mutex::new(Vec::new()));
+            let recorded = seen.clone();
+            let options = RequestOptions {
+                request_id: Some(id.to_string()),
+                progress: Some(Arc::new(move |params: &ProgressParams| {
+                    recorded.lock().unwrap().push(params.message.clone().unwrap_or_default());
+                })),
+                ..Default::default()
+            };
+            (seen, client.call_tool_with_options(id, HashMap::new(), options))
+        };
+        let (first_seen, first) = call("first");
+        let (second_seen, second) = call("second");
+        let (first, second) = tokio::join!(first, second);
+        first.unwrap();
+        second.unwrap();
+
+        assert_eq!(*first_seen.lock().unwrap(), ["first"]);
+        assert_eq!(*second_seen.lock().unwrap(), ["second"]);
+        let progress = next_event(&mut events, "Progress").await;
+        assert_eq!(progress["call_id"], progress["message"]);
+        assert!(client.shared.progress_routes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn health_monitor_disconnects_after_failure_threshold() {
+        // 只应答握手，ping一律不回复
+        let server = MockServer::new(|_, request| match request["method"].as_str() {
+            Some("ping") => Vec::new(),
+            _ => answer_defaults(request),
+        });
+        let client: McpClient<MockTransport> = McpClient::new();
+        let mut events = capture_events(&client);
+        client.set_health_check(Some(HealthCheckConfig {
+            interval: Duration::from_millis(30),
+            timeout: Duration::from_millis(20),
+            failure_threshold: 2,
+        }));
+        client.connect(server.transport()).await.unwrap();
+        assert_eq!(next_event(&mut events, "ConnectionState").await["connected"], true);
+
+        assert_eq!(next_event(&mut events, "ConnectionState").await["connected"], false);
+        assert!(!client.is_connected());
+        assert!(server.methods().iter().filter(|method| *method == "ping").count() >= 2);
+    }
+
+    /// 建立启用自动重连的连接，服务器只在重连后的连接上应答工具调用
+    async fn reconnecting_client(in_flight: InFlightPolicy) -> (MockServer, Arc<McpClient<MockTransport>>, mpsc::UnboundedReceiver<Value>) {
+        let server = MockServer::new(|server, request| match request["method"].as_str() {
+            Some("tools/call") if server.connection_count() > 1 => vec![response(request, text_result("replayed"))],
+            _ => answer_defaults(request),
+        });
+        let client = Arc::new(McpClient::new());
+        let events = capture_events(&client);
+        client.set_reconnect(Some(ReconnectConfig {
+            retry: RetryConfig { max_retries: 3, initial_delay_ms: 10, backoff_factor: 1.0, max_delay_ms: 10 },
+            in_flight,
+        }));
+        let connector = server.clone();
+        client.connect_with(move || {
+            let transport = connector.transport();
+            async move { Ok(transport) }
+        }).await.unwrap();
+        (server, client, events)
+    }
+
+    /// 在后台调用工具，等待请求发出后返回
+    async fn call_in_background(server: &MockServer, client: &Arc<McpClient<MockTransport>>) -> tokio::task::JoinHandle<Result<McpResponse>> {
+        let caller = client.clone();
+        let call = tokio::spawn(async move {
+            let options = RequestOptions { request_id: Some("in-flight".to_string()), ..Default::default() };
+            caller.call_tool_with_options("echo", HashMap::new(), options).await
+        });
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !server.methods().iter().any(|method| method == "tools/call") {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("工具调用未发出");
+        call
+    }
+
+    fn tool_call_ids(server: &MockServer) -> Vec<Value> {
+        server.sent().into_iter()
+            .filter(|message| message["method"] == "tools/call")
+            .map(|message| message["id"].clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reconnect_replays_in_flight_requests() {
+        let (server, client, mut events) = reconnecting_client(InFlightPolicy::Replay).await;
+        assert_eq!(next_event(&mut events, "ConnectionState").await["connected"], true);
+        let call = call_in_background(&server, &client).await;
+
+        server.disconnect();
+
+        assert_eq!(next_event(&mut events, "ConnectionState").await["connected"], false);
+        assert_eq!(next_event(&mut events, "Reconnecting").await["attempt"], 1);
+        assert_eq!(next_event(&mut events, "Reconnected").await["attempt"], 1);
+        let result = call.await.unwrap().unwrap();
+        assert!(matches!(&result.content[..], [McpContent::Text { text, .. }] if text == "replayed"));
+        assert_eq!(tool_call_ids(&server), [json!("in-flight"), json!("in-flight")]);
+        assert!(client.is_connected());
+        assert_eq!(server.connection_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn reconnect_fails_in_flight_requests() {
+        let (server, client, mut events) = reconnecting_client(InFlightPolicy::Fail).await;
+        assert_eq!(next_event(&mut events, "ConnectionState").await["connected"], true);
+        let call = call_in_background(&server, &client).await;
+
+        server.disconnect();
+
+        assert!(matches!(call.await.unwrap(), Err(McpError::Disconnected)));
+        assert_eq!(next_event(&mut events, "ConnectionState").await["connected"], false);
+        assert_eq!(next_event(&mut events, "Reconnected").await["attempt"], 1);
+        assert_eq!(tool_call_ids(&server).len(), 1);
+        assert!(client.is_connected());
     }
-} 
\ No newline at end of file
+}
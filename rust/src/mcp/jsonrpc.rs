@@ -0,0 +1,577 @@
+use crate::mcp::protocol::{McpMessage, McpResource, McpResponse, McpServerInfo};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/// JSON-RPC协议版本
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// 旧版标签格式中握手请求所使用的固定ID
+///
+/// 旧版握手消息不携带ID，编解码时统一映射到该ID
+pub const LEGACY_HANDSHAKE_ID: &str = "handshake";
+
+/// 旧版标签格式的协议版本
+pub const LEGACY_PROTOCOL_VERSION: &str = "1.0";
+
+/// 标准JSON-RPC错误码
+pub mod error_codes {
+    /// 无法解析JSON
+    pub const PARSE_ERROR: i64 = -32700;
+    /// 无效请求
+    pub const INVALID_REQUEST: i64 = -32600;
+    /// 方法不存在
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    /// 无效参数
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// 内部错误
+    pub const INTERNAL_ERROR: i64 = -32603;
+}
+
+// ===== 消息结构 =====
+
+/// 请求ID (字符串或数字)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RequestId {
+    /// 数字ID
+    Number(i64),
+    /// 字符串ID
+    String(String),
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestId::Number(id) => write!(f, "{}", id),
+            RequestId::String(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl From<String> for RequestId {
+    fn from(id: String) -> Self {
+        RequestId::String(id)
+    }
+}
+
+impl From<&str> for RequestId {
+    fn from(id: &str) -> Self {
+        RequestId::String(id.to_string())
+    }
+}
+
+/// JSON-RPC请求 (需要对端响应)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    /// 协议版本，固定为"2.0"
+    pub jsonrpc: String,
+    /// 请求ID
+    pub id: RequestId,
+    /// 方法名
+    pub method: String,
+    /// 参数 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcRequest {
+    /// 创建新的请求
+    pub fn new(id: impl Into<RequestId>, method: &str, params: Option<Value>) -> Self {
+        JsonRpcRequest {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: id.into(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+/// JSON-RPC通知 (无需响应)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcNotification {
+    /// 协议版本，固定为"2.0"
+    pub jsonrpc: String,
+    /// 方法名
+    pub method: String,
+    /// 参数 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcNotification {
+    /// 创建新的通知
+    pub fn new(method: &str, params: Option<Value>) -> Self {
+        JsonRpcNotification {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+/// JSON-RPC响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    /// 协议版本，固定为"2.0"
+    pub jsonrpc: String,
+    /// 对应的请求ID (无法确定请求时为null)
+    pub id: Option<RequestId>,
+    /// 成功结果
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// 错误信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    /// 创建成功响应
+    pub fn success(id: RequestId, result: Value) -> Self {
+        JsonRpcResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// 创建错误响应
+    pub fn failure(id: Option<RequestId>, error: JsonRpcError) -> Self {
+        JsonRpcResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// JSON-RPC错误对象
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    /// 错误码
+    pub code: i64,
+    /// 错误消息
+    pub message: String,
+    /// 附加数据 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    /// 创建新的错误对象
+    pub fn new(code: i64, message: &str) -> Self {
+        JsonRpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    /// 获取字符串形式的错误码
+    ///
+    /// 旧版标签格式使用字符串错误码，转换时保存在`data.legacy_code`中
+    pub fn code_string(&self) -> String {
+        self.data
+            .as_ref()
+            .and_then(|data| data.get("legacy_code"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| self.code.to_string())
+    }
+}
+
+/// JSON-RPC消息 (请求、通知或响应)
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum JsonRpcMessage {
+    /// 请求
+    Request(JsonRpcRequest),
+    /// 通知
+    Notification(JsonRpcNotification),
+    /// 响应
+    Response(JsonRpcResponse),
+}
+
+impl<'de> Deserialize<'de> for JsonRpcMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // 按字段区分消息种类：有method和id为请求，只有method为通知，否则为响应
+        let value = Value::deserialize(deserializer)?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| D::Error::custom("JSON-RPC消息必须是对象"))?;
+
+        if object.get("jsonrpc").and_then(Value::as_str) != Some(JSONRPC_VERSION) {
+            return Err(D::Error::custom("缺少或不支持的jsonrpc版本"));
+        }
+
+        let message = if object.contains_key("method") {
+            if object.contains_key("id") {
+                serde_json::from_value(value).map(JsonRpcMessage::Request)
+            } else {
+                serde_json::from_value(value).map(JsonRpcMessage::Notification)
+            }
+        } else if object.contains_key("result") || object.contains_key("error") {
+            serde_json::from_value(value).map(JsonRpcMessage::Response)
+        } else {
+            return Err(D::Error::custom("无法识别的JSON-RPC消息"));
+        };
+
+        message.map_err(D::Error::custom)
+    }
+}
+
+// ===== 编解码 =====
+
+/// 线路格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireFormat {
+    /// 标准JSON-RPC 2.0格式
+    #[default]
+    JsonRpc,
+    /// 旧版`{"type": ..., "data": ...}`标签格式
+    Tagged,
+}
+
+impl WireFormat {
+    /// 根据消息帧内容判断其线路格式
+    pub fn detect(frame: &str) -> Option<WireFormat> {
+        let value: Value = serde_json::from_str(frame).ok()?;
+        Self::detect_value(&value)
+    }
+
+    fn detect_value(value: &Value) -> Option<WireFormat> {
+        let object = value.as_object()?;
+        if object.contains_key("jsonrpc") {
+            Some(WireFormat::JsonRpc)
+        } else if object.contains_key("type") {
+            Some(WireFormat::Tagged)
+        } else {
+            None
+        }
+    }
+}
+
+/// 编解码错误
+#[derive(Debug)]
+pub enum CodecError {
+    /// JSON格式错误
+    Json(serde_json::Error),
+    /// 无法识别的线路格式
+    UnknownFormat,
+    /// 消息无法用目标格式表示
+    Unsupported(String),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Json(e) => write!(f, "JSON错误: {}", e),
+            CodecError::UnknownFormat => write!(f, "无法识别的消息格式"),
+            CodecError::Unsupported(msg) => write!(f, "格式不支持: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl From<serde_json::Error> for CodecError {
+    fn from(e: serde_json::Error) -> Self {
+        CodecError::Json(e)
+    }
+}
+
+/// 解码消息帧，自动识别线路格式
+///
+/// 旧版标签格式的消息会被转换为等价的JSON-RPC消息
+pub fn decode_frame(frame: &str) -> Result<(WireFormat, JsonRpcMessage), CodecError> {
+    let value: Value = serde_json::from_str(frame)?;
+    match WireFormat::detect_value(&value) {
+        Some(WireFormat::JsonRpc) => Ok((WireFormat::JsonRpc, serde_json::from_value(value)?)),
+        Some(WireFormat::Tagged) => {
+            let legacy: McpMessage = serde_json::from_value(value)?;
+            Ok((WireFormat::Tagged, from_legacy(legacy)?))
+        }
+        None => Err(CodecError::UnknownFormat),
+    }
+}
+
+/// 按指定线路格式编码消息
+///
+/// 返回`None`表示该消息在目标格式中没有对应表示 (如旧版格式下的通知)，应直接丢弃
+pub fn encode_frame(message: &JsonRpcMessage, format: WireFormat) -> Result<Option<String>, CodecError> {
+    match format {
+        WireFormat::JsonRpc => Ok(Some(serde_json::to_string(message)?)),
+        WireFormat::Tagged => match to_legacy(message)? {
+            Some(legacy) => Ok(Some(serde_json::to_string(&legacy)?)),
+            None => Ok(None),
+        },
+    }
+}
+
+/// 将旧版标签消息转换为JSON-RPC消息
+pub fn from_legacy(message: McpMessage) -> Result<JsonRpcMessage, CodecError> {
+    let converted = match message {
+        McpMessage::ToolCall { call_id, name, parameters } => JsonRpcMessage::Request(JsonRpcRequest::new(
+            call_id,
+            "tools/call",
+            Some(json!({ "name": name, "arguments": parameters })),
+        )),
+        McpMessage::ToolResponse { call_id, response } => {
+            JsonRpcMessage::Response(JsonRpcResponse::success(call_id.into(), serde_json::to_value(response)?))
+        }
+        McpMessage::ResourceRequest { request_id, uri } => JsonRpcMessage::Request(JsonRpcRequest::new(
+            request_id,
+            "resources/read",
+            Some(json!({ "uri": uri })),
+        )),
+        McpMessage::ResourceResponse { request_id, resource } => {
+            JsonRpcMessage::Response(JsonRpcResponse::success(request_id.into(), serde_json::to_value(resource)?))
+        }
+        McpMessage::Error { code, message, reference_id } => {
+            let mut error = JsonRpcError::new(error_codes::INTERNAL_ERROR, &message);
+            match code.parse::<i64>() {
+                Ok(numeric) => error.code = numeric,
+                Err(_) => error.data = Some(json!({ "legacy_code": code })),
+            }
+            JsonRpcMessage::Response(JsonRpcResponse::failure(reference_id.map(RequestId::from), error))
+        }
+        McpMessage::Handshake { version, server_info } => {
+            let mut result = Map::new();
+            result.insert("protocolVersion".to_string(), Value::String(version));
+            if let Some(info) = server_info {
+                result.insert("serverInfo".to_string(), serde_json::to_value(info)?);
+            }
+            JsonRpcMessage::Response(JsonRpcResponse::success(LEGACY_HANDSHAKE_ID.into(), Value::Object(result)))
+        }
+    };
+
+    Ok(converted)
+}
+
+/// 将JSON-RPC消息转换为旧版标签消息
+///
+/// 通知在旧版格式中没有对应表示，返回`None`
+pub fn to_legacy(message: &JsonRpcMessage) -> Result<Option<McpMessage>, CodecError> {
+    match message {
+        JsonRpcMessage::Request(request) => {
+            let params = request.params.clone().unwrap_or(Value::Null);
+            let legacy = match request.method.as_str() {
+                "initialize" => McpMessage::Handshake {
+                    version: params
+                        .get("protocolVersion")
+                        .and_then(Value::as_str)
+                        .unwrap_or(LEGACY_PROTOCOL_VERSION)
+                        .to_string(),
+                    server_info: None,
+                },
+                "tools/call" => McpMessage::ToolCall {
+                    call_id: request.id.to_string(),
+                    name: params
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| CodecError::Unsupported("tools/call缺少name参数".to_string()))?
+                        .to_string(),
+                    parameters: match params.get("arguments") {
+                        Some(arguments) => serde_json::from_value::<HashMap<String, Value>>(arguments.clone())?,
+                        None => HashMap::new(),
+                    },
+                },
+                "resources/read" => McpMessage::ResourceRequest {
+                    request_id: request.id.to_string(),
+                    uri: params
+                        .get("uri")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| CodecError::Unsupported("resources/read缺少uri参数".to_string()))?
+                        .to_string(),
+                },
+                other => return Err(CodecError::Unsupported(format!("旧版格式不支持方法 {}", other))),
+            };
+            Ok(Some(legacy))
+        }
+        JsonRpcMessage::Notification(_) => Ok(None),
+        JsonRpcMessage::Response(response) => {
+            if let Some(error) = &response.error {
+                return Ok(Some(McpMessage::Error {
+                    code: error.code_string(),
+                    message: error.message.clone(),
+                    reference_id: response.id.as_ref().map(RequestId::to_string),
+                }));
+            }
+
+            // 旧版格式的响应按结果结构区分类型
+            let result = response.result.clone().unwrap_or(Value::Null);
+            let id = response.id.as_ref().map(RequestId::to_string).unwrap_or_default();
+            let legacy = if result.get("content").is_some() {
+                McpMessage::ToolResponse {
+                    call_id: id,
+                    response: serde_json::from_value::<McpResponse>(result)?,
+                }
+            } else if result.get("contents").is_some() {
+                McpMessage::ResourceResponse {
+                    request_id: id,
+                    resource: serde_json::from_value::<McpResource>(result)?,
+                }
+            } else if let Some(version) = result.get("protocolVersion").and_then(Value::as_str) {
+                McpMessage::Handshake {
+                    version: version.to_string(),
+                    server_info: match result.get("serverInfo") {
+                        Some(info) => Some(serde_json::from_value::<McpServerInfo>(info.clone())?),
+                        None => None,
+                    },
+                }
+            } else {
+                return Err(CodecError::Unsupported("旧版格式无法表示该响应".to_string()));
+            };
+            Ok(Some(legacy))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 编码为指定格式后解析为JSON，便于比较
+    fn encode_value(message: &JsonRpcMessage, format: WireFormat) -> Option<Value> {
+        encode_frame(message, format)
+            .unwrap()
+            .map(|frame| serde_json::from_str(&frame).unwrap())
+    }
+
+    #[test]
+    fn detects_wire_format() {
+        assert_eq!(WireFormat::detect(r#"{"jsonrpc":"2.0","method":"ping","id":1}"#), Some(WireFormat::JsonRpc));
+        assert_eq!(WireFormat::detect(r#"{"type":"handshake","data":{"version":"1.0"}}"#), Some(WireFormat::Tagged));
+        assert_eq!(WireFormat::detect(r#"{"id":1}"#), None);
+        assert_eq!(WireFormat::detect(r#"[{"jsonrpc":"2.0"}]"#), None);
+        assert_eq!(WireFormat::detect("not json"), None);
+    }
+
+    #[test]
+    fn decodes_json_rpc_message_kinds() {
+        let (format, message) = decode_frame(r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#).unwrap();
+        assert_eq!(format, WireFormat::JsonRpc);
+        assert!(matches!(message, JsonRpcMessage::Request(request) if request.id == RequestId::Number(7)));
+
+        let (_, message) = decode_frame(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).unwrap();
+        assert!(matches!(message, JsonRpcMessage::Notification(_)));
+
+        let (_, message) = decode_frame(r#"{"jsonrpc":"2.0","id":"a","result":{}}"#).unwrap();
+        assert!(matches!(message, JsonRpcMessage::Response(response) if response.id == Some("a".into())));
+
+        let (_, message) = decode_frame(r#"{"jsonrpc":"2.0","id":null,"error":{"code":-32700,"message":"parse"}}"#).unwrap();
+        assert!(matches!(message, JsonRpcMessage::Response(response) if response.id.is_none() && response.error.is_some()));
+    }
+
+    #[test]
+    fn rejects_invalid_frames() {
+        assert!(matches!(decode_frame(r#"{"jsonrpc":"1.0","id":1,"method":"ping"}"#), Err(CodecError::Json(_))));
+        assert!(matches!(decode_frame(r#"{"jsonrpc":"2.0","id":1}"#), Err(CodecError::Json(_))));
+        assert!(matches!(decode_frame(r#"{"id":1}"#), Err(CodecError::UnknownFormat)));
+        assert!(matches!(decode_frame("{"), Err(CodecError::Json(_))));
+    }
+
+    #[test]
+    fn json_rpc_round_trip() {
+        let request = JsonRpcMessage::Request(JsonRpcRequest::new(
+            "call-1",
+            "tools/call",
+            Some(json!({ "name": "search", "arguments": { "q": "rust" } })),
+        ));
+
+        let frame = encode_frame(&request, WireFormat::JsonRpc).unwrap().unwrap();
+        let (format, decoded) = decode_frame(&frame).unwrap();
+        assert_eq!(format, WireFormat::JsonRpc);
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&request).unwrap());
+    }
+
+    #[test]
+    fn legacy_tool_call_round_trip() {
+        let frame = json!({
+            "type": "tool_call",
+            "data": { "call_id": "c1", "name": "search", "parameters": { "q": "rust" } },
+        });
+
+        let (format, message) = decode_frame(&frame.to_string()).unwrap();
+        assert_eq!(format, WireFormat::Tagged);
+        match &message {
+            JsonRpcMessage::Request(request) => {
+                assert_eq!(request.id, RequestId::from("c1"));
+                assert_eq!(request.method, "tools/call");
+                assert_eq!(request.params, Some(json!({ "name": "search", "arguments": { "q": "rust" } })));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        assert_eq!(encode_value(&message, WireFormat::Tagged), Some(frame));
+    }
+
+    #[test]
+    fn legacy_handshake_uses_fixed_id() {
+        // 客户端的initialize请求编码为不带ID的旧版握手
+        let initialize = JsonRpcMessage::Request(JsonRpcRequest::new(
+            LEGACY_HANDSHAKE_ID,
+            "initialize",
+            Some(json!({ "protocolVersion": LEGACY_PROTOCOL_VERSION })),
+        ));
+        assert_eq!(
+            encode_value(&initialize, WireFormat::Tagged),
+            Some(json!({ "type": "handshake", "data": { "version": "1.0", "server_info": null } })),
+        );
+
+        // 服务器的握手回复解码为对应该ID的响应
+        let frame = json!({
+            "type": "handshake",
+            "data": { "version": "1.0", "server_info": { "name": "legacy", "version": "0.1", "tools": [] } },
+        });
+        let (_, message) = decode_frame(&frame.to_string()).unwrap();
+        match &message {
+            JsonRpcMessage::Response(response) => {
+                assert_eq!(response.id, Some(RequestId::from(LEGACY_HANDSHAKE_ID)));
+                let result = response.result.as_ref().unwrap();
+                assert_eq!(result["protocolVersion"], "1.0");
+                assert_eq!(result["serverInfo"]["name"], "legacy");
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        assert_eq!(encode_value(&message, WireFormat::Tagged), Some(frame));
+    }
+
+    #[test]
+    fn legacy_error_codes_round_trip() {
+        let frame = json!({
+            "type": "error",
+            "data": { "code": "tool_not_found", "message": "missing", "reference_id": "c1" },
+        });
+        let (_, message) = decode_frame(&frame.to_string()).unwrap();
+        match &message {
+            JsonRpcMessage::Response(response) => {
+                let error = response.error.as_ref().unwrap();
+                assert_eq!(error.code, error_codes::INTERNAL_ERROR);
+                assert_eq!(error.code_string(), "tool_not_found");
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert_eq!(encode_value(&message, WireFormat::Tagged), Some(frame));
+
+        // 数字错误码直接作为JSON-RPC错误码
+        let frame = json!({ "type": "error", "data": { "code": "-32601", "message": "missing", "reference_id": null } });
+        let (_, message) = decode_frame(&frame.to_string()).unwrap();
+        assert!(matches!(message, JsonRpcMessage::Response(response) if response.error.as_ref().unwrap().code == -32601));
+    }
+
+    #[test]
+    fn legacy_format_drops_notifications_and_rejects_unknown_methods() {
+        let notification = JsonRpcMessage::Notification(JsonRpcNotification::new("notifications/initialized", None));
+        assert!(encode_frame(&notification, WireFormat::Tagged).unwrap().is_none());
+
+        let request = JsonRpcMessage::Request(JsonRpcRequest::new("p1", "prompts/list", None));
+        assert!(matches!(encode_frame(&request, WireFormat::Tagged), Err(CodecError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::mcp::client::{ConnectionHealth, McpClient, McpError, RNCallback, RequestOptions};
+use crate::mcp::protocol::{McpResponse, McpTool};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+type Result<T> = std::result::Result<T, McpError>;
+
+/// 聚合工具名中服务器ID与工具名之间的分隔符
+pub const TOOL_NAME_SEPARATOR: &str = "__";
+
+/// 带服务器命名空间的工具
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespacedTool {
+    /// 带服务器前缀的工具名 (`服务器ID__工具名`)，不同服务器的同名工具不会冲突
+    pub name: String,
+    /// 提供该工具的服务器ID
+    pub server_id: String,
+    /// 服务器返回的工具定义
+    pub tool: McpTool,
+}
+
+/// 单个服务器的连接状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerState {
+    /// 服务器ID
+    pub server_id: String,
+    /// 握手时服务器报告的名称
+    pub server_name: Option<String>,
+    /// 连接健康状态
+    pub health: ConnectionHealth,
+}
+
+/// 多服务器连接管理器
+///
+/// 按服务器ID管理多个客户端，聚合各服务器的工具并将工具调用路由到对应服务器；
+/// 各客户端的事件会附带服务器ID转发给React Native: `{"server_id": ..., "event": ...}`
+///
+/// 所有操作都只需共享借用，连接某个服务器期间仍可调用其他服务器
+pub struct McpClientManager {
+    /// 按服务器ID保存的客户端
+    clients: Mutex<BTreeMap<String, Arc<McpClient>>>,
+    /// 与React Native的交互回调
+    rn_callback: Mutex<Option<RNCallback>>,
+}
+
+impl McpClientManager {
+    /// 创建空的管理器
+    pub fn new() -> Self {
+        McpClientManager {
+            clients: Mutex::new(BTreeMap::new()),
+            rn_callback: Mutex::new(None),
+        }
+    }
+
+    /// 设置React Native回调，已添加的客户端会立即使用新回调
+    pub fn set_rn_callback(&self, callback: RNCallback) {
+        for (server_id, client) in self.clients.lock().unwrap().iter() {
+            client.set_rn_callback(server_callback(server_id, &callback));
+        }
+        *self.rn_callback.lock().unwrap() = Some(callback);
+    }
+
+    /// 添加已配置好的客户端 (可在连接前注册处理器、设置根目录等)
+    ///
+    /// 服务器ID不能为空或包含`TOOL_NAME_SEPARATOR`，也不能与已有服务器重复
+    pub fn add_server(&self, server_id: &str, client: McpClient) -> Result<()> {
+        if server_id.is_empty() || server_id.contains(TOOL_NAME_SEPARATOR) {
+            return Err(McpError::InternalError(format!(
+                "服务器ID不能为空或包含{}: {}",
+                TOOL_NAME_SEPARATOR, server_id
+            )));
+        }
+
+        let mut clients = self.clients.lock().unwrap();
+        if clients.contains_key(server_id) {
+            return Err(McpError::InternalError(format!("服务器ID已存在: {}", server_id)));
+        }
+
+        if let Some(callback) = self.rn_callback.lock().unwrap().as_ref() {
+            client.set_rn_callback(server_callback(server_id, callback));
+        }
+        clients.insert(server_id.to_string(), Arc::new(client));
+        Ok(())
+    }
+
+    /// 连接到指定服务器，服务器ID未添加时使用默认配置的客户端；已连接时替换原连接
+    pub async fn connect(&self, server_id: &str, server_url: &str) -> Result<()> {
+        if self.client(server_id).is_none() {
+            self.add_server(server_id, McpClient::new())?;
+        }
+
+        let client = self.client(server_id)
+            .ok_or_else(|| McpError::UnknownServer(server_id.to_string()))?;
+        // 替换已有连接时先关闭旧传输
+        if client.is_connected() {
+            client.disconnect().await?;
+        }
+        client.connect_url(server_url).await
+    }
+
+    /// 断开指定服务器的连接，客户端及其配置保留
+    pub async fn disconnect(&self, server_id: &str) -> Result<()> {
+        let client = self.client(server_id)
+            .ok_or_else(|| McpError::UnknownServer(server_id.to_string()))?;
+        client.disconnect().await
+    }
+
+    /// 断开并移除指定服务器
+    pub async fn remove_server(&self, server_id: &str) -> Result<()> {
+        let client = self.clients.lock().unwrap().remove(server_id)
+            .ok_or_else(|| McpError::UnknownServer(server_id.to_string()))?;
+        client.disconnect().await
+    }
+
+    /// 断开所有服务器的连接
+    pub async fn disconnect_all(&self) {
+        for (server_id, client) in self.snapshot() {
+            if let Err(e) = client.disconnect().await {
+                log::warn!("断开服务器{}失败: {}", server_id, e);
+            }
+        }
+    }
+
+    /// 获取指定服务器的客户端
+    pub fn client(&self, server_id: &str) -> Option<Arc<McpClient>> {
+        self.clients.lock().unwrap().get(server_id).cloned()
+    }
+
+    /// 已添加的服务器ID (按字典序)
+    pub fn server_ids(&self) -> Vec<String> {
+        self.clients.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// 各服务器的连接状态
+    pub fn server_states(&self) -> Vec<ServerState> {
+        self.snapshot().into_iter()
+            .map(|(server_id, client)| ServerState {
+                server_name: client.get_server_info().map(|info| info.name),
+                health: client.connection_health(),
+                server_id,
+            })
+            .collect()
+    }
+
+    /// 聚合所有已连接服务器的工具
+    ///
+    /// 工具名统一加上服务器前缀；获取失败的服务器会被跳过，不影响其他服务器
+    pub async fn list_tools(&self) -> Vec<NamespacedTool> {
+        let mut tools = Vec::new();
+
+        for (server_id, client) in self.snapshot() {
+            if !client.is_connected() {
+                continue;
+            }
+
+            match client.list_tools().await {
+                Ok(server_tools) => tools.extend(server_tools.into_iter().map(|tool| NamespacedTool {
+                    name: namespaced_tool_name(&server_id, &tool.name),
+                    server_id: server_id.clone(),
+                    tool,
+                })),
+                Err(e) => log::warn!("获取服务器{}的工具列表失败: {}", server_id, e),
+            }
+        }
+
+        tools
+    }
+
+    /// 调用工具，按工具名路由到对应服务器
+    pub async fn call_tool(&self, name: &str, parameters: HashMap<String, Value>) -> Result<McpResponse> {
+        self.call_tool_with_options(name, parameters, RequestOptions::default()).await
+    }
+
+    /// 使用指定选项调用工具，按工具名路由到对应服务器
+    pub async fn call_tool_with_options(
+        &self,
+        name: &str,
+        parameters: HashMap<String, Value>,
+        options: RequestOptions,
+    ) -> Result<McpResponse> {
+        let (client, tool_name) = self.resolve_tool(name).await?;
+        client.call_tool_with_options(&tool_name, parameters, options).await
+    }
+
+    /// 当前所有客户端，等待异步调用前先取出，不在等待期间持有锁
+    fn snapshot(&self) -> Vec<(String, Arc<McpClient>)> {
+        self.clients.lock().unwrap().iter()
+            .map(|(server_id, client)| (server_id.clone(), client.clone()))
+            .collect()
+    }
+
+    /// 查找提供工具的服务器
+    ///
+    /// 带服务器前缀的名称直接路由；不带前缀时只有恰好一个已连接服务器提供该工具才能路由
+    async fn resolve_tool(&self, name: &str) -> Result<(Arc<McpClient>, String)> {
+        if let Some((server_id, tool_name)) = name.split_once(TOOL_NAME_SEPARATOR) {
+            if let Some(client) = self.client(server_id) {
+                return Ok((client, tool_name.to_string()));
+            }
+        }
+
+        let mut owners = Vec::new();
+        for (_, client) in self.snapshot() {
+            if !client.is_connected() {
+                continue;
+            }
+            if let Ok(tools) = client.list_tools().await {
+                if tools.iter().any(|tool| tool.name == name) {
+                    owners.push(client);
+                }
+            }
+        }
+
+        match owners.len() {
+            1 => Ok((owners.remove(0), name.to_string())),
+            0 => Err(McpError::ProtocolError(format!("没有服务器提供工具: {}", name))),
+            _ => Err(McpError::ProtocolError(format!(
+                "多个服务器提供工具{}，请使用带服务器前缀的名称",
+                name
+            ))),
+        }
+    }
+}
+
+impl Default for McpClientManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 生成带服务器前缀的工具名
+pub fn namespaced_tool_name(server_id: &str, tool_name: &str) -> String {
+    format!("{}{}{}", server_id, TOOL_NAME_SEPARATOR, tool_name)
+}
+
+/// 为单个服务器包装React Native回调，在事件中附带服务器ID
+fn server_callback(server_id: &str, callback: &RNCallback) -> RNCallback {
+    let server_id = server_id.to_string();
+    let handler = callback.handler.clone();
+
+    RNCallback {
+        callback_id: format!("{}:{}", callback.callback_id, server_id),
+        handler: Arc::new(move |event_json: String| {
+            let event = match serde_json::from_str::<Value>(&event_json) {
+                Ok(event) => event,
+                Err(_) => Value::String(event_json),
+            };
+            handler(json!({ "server_id": server_id, "event": event }).to_string());
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::protocol::McpContent;
+    use crate::mcp::transport::mock::{answer_defaults, response, text_result, tool, MockServer};
+
+    /// 连接到提供指定工具的模拟服务器，工具调用返回`服务器名:工具名`
+    async fn connected_client(server_name: &'static str, tools: &[&str]) -> McpClient {
+        let tools: Vec<Value> = tools.iter().map(|name| tool(name)).collect();
+        let server = MockServer::new(move |_, request| match request["method"].as_str() {
+            Some("tools/list") => vec![response(request, json!({ "tools": tools }))],
+            Some("tools/call") => {
+                let text = format!("{}:{}", server_name, request["params"]["name"].as_str().unwrap());
+                vec![response(request, text_result(&text))]
+            }
+            _ => answer_defaults(request),
+        });
+        let client: McpClient = McpClient::new();
+        client.connect(Box::new(server.transport())).await.unwrap();
+        client
+    }
+
+    async fn manager_with(servers: &[(&'static str, &[&str])]) -> McpClientManager {
+        let manager = McpClientManager::new();
+        for (server_id, tools) in servers {
+            manager.add_server(server_id, connected_client(server_id, tools).await).unwrap();
+        }
+        manager
+    }
+
+    fn text(response: &McpResponse) -> &str {
+        match &response.content[..] {
+            [McpContent::Text { text, .. }] => text,
+            other => panic!("unexpected content: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn namespaced_tool_name_joins_with_separator() {
+        assert_eq!(namespaced_tool_name("fs", "read"), "fs__read");
+    }
+
+    #[test]
+    fn add_server_validates_server_id() {
+        let manager = McpClientManager::new();
+
+        assert!(manager.add_server("", McpClient::new()).is_err());
+        assert!(manager.add_server("a__b", McpClient::new()).is_err());
+        manager.add_server("fs", McpClient::new()).unwrap();
+        assert!(manager.add_server("fs", McpClient::new()).is_err());
+        assert_eq!(manager.server_ids(), ["fs"]);
+    }
+
+    #[tokio::test]
+    async fn list_tools_prefixes_server_id() {
+        let manager = manager_with(&[("fs", &["read"]), ("web", &["fetch", "read"])]).await;
+
+        let names: Vec<_> = manager.list_tools().await.into_iter().map(|tool| tool.name).collect();
+        assert_eq!(names, ["fs__read", "web__fetch", "web__read"]);
+    }
+
+    #[tokio::test]
+    async fn call_tool_routes_prefixed_name_to_its_server() {
+        let manager = manager_with(&[("fs", &["read"]), ("web", &["read"])]).await;
+
+        let result = manager.call_tool("web__read", HashMap::new()).await.unwrap();
+        assert_eq!(text(&result), "web:read");
+    }
+
+    #[tokio::test]
+    async fn call_tool_routes_unique_unprefixed_name() {
+        let manager = manager_with(&[("fs", &["read"]), ("web", &["fetch"])]).await;
+
+        let result = manager.call_tool("fetch", HashMap::new()).await.unwrap();
+        assert_eq!(text(&result), "web:fetch");
+    }
+
+    #[tokio::test]
+    async fn call_tool_rejects_ambiguous_or_unknown_name() {
+        let manager = manager_with(&[("fs", &["read"]), ("web", &["read"])]).await;
+
+        assert!(matches!(manager.call_tool("read", HashMap::new()).await, Err(McpError::ProtocolError(_))));
+        assert!(matches!(manager.call_tool("write", HashMap::new()).await, Err(McpError::ProtocolError(_))));
+    }
+}
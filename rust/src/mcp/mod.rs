@@ -1,8 +1,32 @@
 mod protocol;
+mod jsonrpc;
+mod schema;
+mod uri_template;
 mod client;
+mod manager;
+mod registry;
+mod transport;
+mod bridge;
 
-pub use client::McpClient;
-pub use protocol::{McpMessage, McpTool, McpResource, McpResponse};
+pub use client::{
+    McpClient, McpError, RequestOptions, ProgressCallback, LogSink, HealthCheckConfig, ConnectionHealth, HandlerFuture,
+    ServerRequestHandler, RetryConfig, ReconnectConfig, InFlightPolicy,
+};
+pub use manager::{McpClientManager, NamespacedTool, ServerState, TOOL_NAME_SEPARATOR, namespaced_tool_name};
+pub use transport::{
+    Transport, StdioTransport, StdioTransportConfig, LogCallback, WebSocketTransport, WebSocketTransportConfig,
+    HttpTransport, HttpTransportConfig, ConnectOptions,
+};
+pub use protocol::{
+    McpMessage, McpTool, McpResource, McpResponse, McpServerInfo, McpImplementation, ClientCapabilities,
+    ServerCapabilities, McpContent, McpResourceContent, ResourceBody, Annotations, Role, text_content, image_content,
+    audio_content, resource_content, resource_link_content, McpResourceInfo, McpResourceTemplate,
+    McpPrompt, McpPromptArgument, McpPromptMessage, GetPromptResult,
+    SamplingMessage, CreateMessageParams, CreateMessageResult, McpRoot, ProgressParams,
+    LoggingLevel, McpLogMessage, CompletionReference, CompletionResult,
+    ElicitRequestParams, ElicitAction, ElicitResult,
+};
+pub use uri_template::{expand_uri_template, template_variables, UriTemplateError};
+pub use schema::{validate as validate_schema, check_restricted_schema, SchemaViolation};
+pub use jsonrpc::{JsonRpcMessage, JsonRpcRequest, JsonRpcNotification, JsonRpcResponse, JsonRpcError, RequestId, WireFormat};
 
-#[cfg(test)]
-mod tests; 
\ No newline at end of file
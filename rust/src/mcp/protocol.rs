@@ -1,6 +1,20 @@
+use crate::mcp::jsonrpc::LEGACY_PROTOCOL_VERSION;
+use crate::mcp::uri_template::{expand_uri_template, template_variables, UriTemplateError};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// 客户端首选的MCP协议版本
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// 客户端支持的MCP协议版本 (按优先级排列)
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[
+    LATEST_PROTOCOL_VERSION,
+    "2025-03-26",
+    "2024-11-05",
+    LEGACY_PROTOCOL_VERSION,
+];
+
 /// MCP消息类型定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
@@ -76,17 +90,210 @@ pub struct McpResponse {
 }
 
 /// MCP内容块
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct McpContent {
-    /// 内容类型
-    #[serde(rename = "type")]
-    pub content_type: String,
-    /// 文本内容 (对于text类型)
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub text: Option<String>,
-    /// 其他属性
-    #[serde(flatten)]
-    pub attributes: HashMap<String, serde_json::Value>,
+///
+/// 无法识别的内容类型保存在`Unknown`中，序列化时原样输出；已知类型中的未知字段会被忽略
+#[derive(Debug, Clone, PartialEq)]
+pub enum McpContent {
+    /// 文本内容
+    Text {
+        /// 文本
+        text: String,
+        /// 注解 (可选)
+        annotations: Option<Annotations>,
+    },
+    /// 图片内容
+    Image {
+        /// Base64编码的图片数据
+        data: String,
+        /// MIME类型
+        mime_type: String,
+        /// 注解 (可选)
+        annotations: Option<Annotations>,
+    },
+    /// 音频内容
+    Audio {
+        /// Base64编码的音频数据
+        data: String,
+        /// MIME类型
+        mime_type: String,
+        /// 注解 (可选)
+        annotations: Option<Annotations>,
+    },
+    /// 内嵌资源
+    Resource {
+        /// 资源内容
+        resource: McpResourceContent,
+        /// 注解 (可选)
+        annotations: Option<Annotations>,
+    },
+    /// 资源链接
+    ResourceLink {
+        /// 资源URI
+        uri: String,
+        /// 资源名称
+        name: String,
+        /// 资源描述 (可选)
+        description: Option<String>,
+        /// MIME类型 (可选)
+        mime_type: Option<String>,
+        /// 注解 (可选)
+        annotations: Option<Annotations>,
+    },
+    /// 未知类型的内容块 (保留原始JSON)
+    Unknown(serde_json::Value),
+}
+
+impl McpContent {
+    /// 内容类型名称 (与协议中的type字段一致)
+    pub fn content_type(&self) -> &str {
+        match self {
+            McpContent::Text { .. } => "text",
+            McpContent::Image { .. } => "image",
+            McpContent::Audio { .. } => "audio",
+            McpContent::Resource { .. } => "resource",
+            McpContent::ResourceLink { .. } => "resource_link",
+            McpContent::Unknown(value) => value.get("type").and_then(|t| t.as_str()).unwrap_or(""),
+        }
+    }
+    
+    /// 文本内容 (仅text类型)
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            McpContent::Text { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+    
+    /// 内容块的注解
+    pub fn annotations(&self) -> Option<&Annotations> {
+        match self {
+            McpContent::Text { annotations, .. }
+            | McpContent::Image { annotations, .. }
+            | McpContent::Audio { annotations, .. }
+            | McpContent::Resource { annotations, .. }
+            | McpContent::ResourceLink { annotations, .. } => annotations.as_ref(),
+            McpContent::Unknown(_) => None,
+        }
+    }
+    
+    /// 设置注解，未知类型的内容块保持不变
+    pub fn with_annotations(mut self, value: Annotations) -> Self {
+        match &mut self {
+            McpContent::Text { annotations, .. }
+            | McpContent::Image { annotations, .. }
+            | McpContent::Audio { annotations, .. }
+            | McpContent::Resource { annotations, .. }
+            | McpContent::ResourceLink { annotations, .. } => *annotations = Some(value),
+            McpContent::Unknown(_) => {}
+        }
+        self
+    }
+}
+
+/// 内容块的协议表示
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentRepr {
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+    Audio {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+    Resource {
+        resource: McpResourceContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+    ResourceLink {
+        uri: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, rename = "mimeType", skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+}
+
+impl Serialize for McpContent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let repr = match self.clone() {
+            McpContent::Text { text, annotations } => ContentRepr::Text { text, annotations },
+            McpContent::Image { data, mime_type, annotations } => ContentRepr::Image { data, mime_type, annotations },
+            McpContent::Audio { data, mime_type, annotations } => ContentRepr::Audio { data, mime_type, annotations },
+            McpContent::Resource { resource, annotations } => ContentRepr::Resource { resource, annotations },
+            McpContent::ResourceLink { uri, name, description, mime_type, annotations } => {
+                ContentRepr::ResourceLink { uri, name, description, mime_type, annotations }
+            }
+            McpContent::Unknown(value) => return value.serialize(serializer),
+        };
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for McpContent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let content_type = match value.get("type").and_then(|t| t.as_str()) {
+            Some(content_type) => content_type,
+            None => return Err(serde::de::Error::custom("内容块缺少type字段")),
+        };
+        
+        // 只有无法识别的类型保留原始JSON，已知类型忽略多余字段，缺少必需字段时报错
+        if !matches!(content_type, "text" | "image" | "audio" | "resource" | "resource_link") {
+            return Ok(McpContent::Unknown(value));
+        }
+        let repr = serde_json::from_value::<ContentRepr>(value).map_err(serde::de::Error::custom)?;
+        
+        Ok(match repr {
+            ContentRepr::Text { text, annotations } => McpContent::Text { text, annotations },
+            ContentRepr::Image { data, mime_type, annotations } => McpContent::Image { data, mime_type, annotations },
+            ContentRepr::Audio { data, mime_type, annotations } => McpContent::Audio { data, mime_type, annotations },
+            ContentRepr::Resource { resource, annotations } => McpContent::Resource { resource, annotations },
+            ContentRepr::ResourceLink { uri, name, description, mime_type, annotations } => {
+                McpContent::ResourceLink { uri, name, description, mime_type, annotations }
+            }
+        })
+    }
+}
+
+/// 消息发送者角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 用户
+    User,
+    /// 模型
+    Assistant,
+}
+
+/// 内容注解，提示客户端如何使用内容
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotations {
+    /// 内容的目标受众 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<Vec<Role>>,
+    /// 重要程度，取值0到1 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<f64>,
+    /// 最后修改时间，ISO 8601格式 (可选)
+    #[serde(default, rename = "lastModified", skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
 }
 
 /// MCP资源
@@ -100,15 +307,103 @@ pub struct McpResource {
 }
 
 /// MCP资源内容
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct McpResourceContent {
     /// 资源URI
     pub uri: String,
-    /// 文本内容
-    pub text: String,
     /// MIME类型 (可选)
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, rename = "mimeType", alias = "mime_type", skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
+    /// 资源数据 (文本或二进制)
+    #[serde(flatten)]
+    pub body: ResourceBody,
+}
+
+/// 资源数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResourceBody {
+    /// 文本资源
+    Text {
+        /// 文本内容
+        text: String,
+    },
+    /// 二进制资源 (协议中为Base64编码)
+    Blob {
+        /// 原始字节
+        #[serde(with = "base64_bytes")]
+        blob: Vec<u8>,
+    },
+}
+
+impl McpResourceContent {
+    /// 创建文本资源内容
+    pub fn text(uri: &str, text: &str, mime_type: Option<&str>) -> Self {
+        McpResourceContent {
+            uri: uri.to_string(),
+            mime_type: mime_type.map(|m| m.to_string()),
+            body: ResourceBody::Text { text: text.to_string() },
+        }
+    }
+    
+    /// 创建二进制资源内容
+    pub fn blob(uri: &str, blob: Vec<u8>, mime_type: Option<&str>) -> Self {
+        McpResourceContent {
+            uri: uri.to_string(),
+            mime_type: mime_type.map(|m| m.to_string()),
+            body: ResourceBody::Blob { blob },
+        }
+    }
+    
+    /// 文本内容 (仅文本资源)
+    pub fn as_text(&self) -> Option<&str> {
+        match &self.body {
+            ResourceBody::Text { text } => Some(text),
+            ResourceBody::Blob { .. } => None,
+        }
+    }
+    
+    /// 二进制内容 (仅二进制资源)
+    pub fn as_blob(&self) -> Option<&[u8]> {
+        match &self.body {
+            ResourceBody::Text { .. } => None,
+            ResourceBody::Blob { blob } => Some(blob),
+        }
+    }
+    
+    /// 是否为二进制资源
+    pub fn is_blob(&self) -> bool {
+        matches!(self.body, ResourceBody::Blob { .. })
+    }
+    
+    /// 实际使用的MIME类型
+    ///
+    /// 服务器未声明时，文本资源视为text/plain，二进制资源视为application/octet-stream
+    pub fn effective_mime_type(&self) -> &str {
+        match (&self.mime_type, &self.body) {
+            (Some(mime_type), _) => mime_type,
+            (None, ResourceBody::Text { .. }) => "text/plain",
+            (None, ResourceBody::Blob { .. }) => "application/octet-stream",
+        }
+    }
+}
+
+/// 二进制数据与Base64字符串之间的序列化
+mod base64_bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+    
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        // 部分服务器会在Base64中插入换行
+        let compact: String = encoded.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+        STANDARD.decode(compact).map_err(|e| serde::de::Error::custom(format!("无效的Base64数据: {}", e)))
+    }
 }
 
 /// MCP工具定义
@@ -117,11 +412,359 @@ pub struct McpTool {
     /// 工具名称
     pub name: String,
     /// 工具描述
+    #[serde(default)]
     pub description: String,
-    /// 参数模式 (JSON Schema格式)
+    /// 参数模式 (JSON Schema格式，标准MCP中为inputSchema)
+    #[serde(alias = "inputSchema")]
     pub parameters_schema: serde_json::Value,
 }
 
+/// 分页列表结果
+pub trait PaginatedResult: DeserializeOwned {
+    /// 列表项类型
+    type Item;
+    
+    /// 拆分为本页列表项和下一页游标
+    fn into_page(self) -> (Vec<Self::Item>, Option<String>);
+}
+
+/// tools/list响应结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListToolsResult {
+    /// 工具列表
+    pub tools: Vec<McpTool>,
+    /// 下一页游标 (可选)
+    #[serde(default, rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl PaginatedResult for ListToolsResult {
+    type Item = McpTool;
+    
+    fn into_page(self) -> (Vec<McpTool>, Option<String>) {
+        (self.tools, self.next_cursor)
+    }
+}
+
+/// resources/list返回的资源描述
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpResourceInfo {
+    /// 资源URI
+    pub uri: String,
+    /// 资源名称
+    pub name: String,
+    /// 资源描述 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME类型 (可选)
+    #[serde(default, rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// 资源大小，单位字节 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// 注解 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Annotations>,
+}
+
+/// 资源模板，URI按RFC 6570模板展开
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpResourceTemplate {
+    /// URI模板 (如 notes://{folder}/{id})
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    /// 模板名称
+    pub name: String,
+    /// 模板描述 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 展开后资源的MIME类型 (可选)
+    #[serde(default, rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// 注解 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Annotations>,
+}
+
+impl McpResourceTemplate {
+    /// 用给定变量展开模板，得到资源URI
+    pub fn expand(&self, variables: &HashMap<String, serde_json::Value>) -> Result<String, UriTemplateError> {
+        expand_uri_template(&self.uri_template, variables)
+    }
+    
+    /// 模板中需要填写的变量名
+    pub fn variables(&self) -> Result<Vec<String>, UriTemplateError> {
+        template_variables(&self.uri_template)
+    }
+}
+
+/// resources/list响应结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourcesResult {
+    /// 资源列表
+    pub resources: Vec<McpResourceInfo>,
+    /// 下一页游标 (可选)
+    #[serde(default, rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl PaginatedResult for ListResourcesResult {
+    type Item = McpResourceInfo;
+    
+    fn into_page(self) -> (Vec<McpResourceInfo>, Option<String>) {
+        (self.resources, self.next_cursor)
+    }
+}
+
+/// resources/templates/list响应结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourceTemplatesResult {
+    /// 资源模板列表
+    #[serde(rename = "resourceTemplates")]
+    pub resource_templates: Vec<McpResourceTemplate>,
+    /// 下一页游标 (可选)
+    #[serde(default, rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl PaginatedResult for ListResourceTemplatesResult {
+    type Item = McpResourceTemplate;
+    
+    fn into_page(self) -> (Vec<McpResourceTemplate>, Option<String>) {
+        (self.resource_templates, self.next_cursor)
+    }
+}
+
+/// 服务器提供的提示词模板
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpPrompt {
+    /// 提示词名称
+    pub name: String,
+    /// 提示词描述 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 提示词参数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/// 提示词参数定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpPromptArgument {
+    /// 参数名称
+    pub name: String,
+    /// 参数描述 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 是否必填
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// 提示词展开后的一条消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpPromptMessage {
+    /// 消息角色
+    pub role: Role,
+    /// 消息内容
+    pub content: McpContent,
+}
+
+/// prompts/get响应结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    /// 提示词描述 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 展开后的消息列表
+    pub messages: Vec<McpPromptMessage>,
+}
+
+/// prompts/list响应结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListPromptsResult {
+    /// 提示词列表
+    pub prompts: Vec<McpPrompt>,
+    /// 下一页游标 (可选)
+    #[serde(default, rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl PaginatedResult for ListPromptsResult {
+    type Item = McpPrompt;
+    
+    fn into_page(self) -> (Vec<McpPrompt>, Option<String>) {
+        (self.prompts, self.next_cursor)
+    }
+}
+
+/// 采样请求中的一条消息
+pub type SamplingMessage = McpPromptMessage;
+
+/// sampling/createMessage请求参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMessageParams {
+    /// 对话消息
+    pub messages: Vec<SamplingMessage>,
+    /// 系统提示词 (可选)
+    #[serde(default, rename = "systemPrompt", skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// 最大生成token数
+    #[serde(rename = "maxTokens")]
+    pub max_tokens: u32,
+    /// 采样温度 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// 停止序列
+    #[serde(default, rename = "stopSequences", skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// 模型偏好 (可选)
+    #[serde(default, rename = "modelPreferences", skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<serde_json::Value>,
+    /// 需要附带的上下文范围: none、thisServer或allServers (可选)
+    #[serde(default, rename = "includeContext", skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<String>,
+    /// 传给模型提供方的元数据 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// sampling/createMessage响应结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMessageResult {
+    /// 消息角色
+    pub role: Role,
+    /// 生成的内容
+    pub content: McpContent,
+    /// 实际使用的模型名称
+    pub model: String,
+    /// 停止原因 (可选)
+    #[serde(default, rename = "stopReason", skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+/// 客户端向服务器公开的根目录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpRoot {
+    /// 根目录URI (必须为file://地址)
+    pub uri: String,
+    /// 显示名称 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// notifications/progress通知参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressParams {
+    /// 请求时附带的进度令牌
+    #[serde(rename = "progressToken")]
+    pub progress_token: serde_json::Value,
+    /// 当前进度 (单调递增)
+    pub progress: f64,
+    /// 总量 (可选，未知时为None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    /// 进度说明 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 服务器日志级别 (按RFC 5424从低到高排列)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggingLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+/// notifications/message通知携带的日志记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpLogMessage {
+    /// 日志级别
+    pub level: LoggingLevel,
+    /// 日志来源 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// 日志内容 (任意JSON)
+    pub data: serde_json::Value,
+}
+
+/// 参数补全的目标
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CompletionReference {
+    /// 提示词参数
+    #[serde(rename = "ref/prompt")]
+    Prompt {
+        /// 提示词名称
+        name: String,
+    },
+    /// 资源模板变量
+    #[serde(rename = "ref/resource")]
+    Resource {
+        /// 资源模板URI
+        uri: String,
+    },
+}
+
+/// 参数补全结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompletionResult {
+    /// 候选值 (最多100个)
+    pub values: Vec<String>,
+    /// 候选值总数 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    /// 是否还有更多候选值
+    #[serde(default, rename = "hasMore")]
+    pub has_more: bool,
+}
+
+/// completion/complete响应结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteResult {
+    /// 补全结果
+    pub completion: CompletionResult,
+}
+
+/// elicitation/create请求参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitRequestParams {
+    /// 展示给用户的说明
+    pub message: String,
+    /// 需要用户填写的字段 (受限的JSON Schema)
+    #[serde(rename = "requestedSchema")]
+    pub requested_schema: serde_json::Value,
+}
+
+/// 用户对elicitation请求的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    /// 提交内容
+    Accept,
+    /// 明确拒绝
+    Decline,
+    /// 未作选择 (如关闭了对话框)
+    Cancel,
+}
+
+/// elicitation/create响应结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitResult {
+    /// 用户的处理方式
+    pub action: ElicitAction,
+    /// 用户填写的内容 (仅accept时存在)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
 /// MCP服务器信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerInfo {
@@ -134,6 +777,107 @@ pub struct McpServerInfo {
     pub tools: Vec<McpTool>,
 }
 
+/// 客户端或服务器的实现信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpImplementation {
+    /// 实现名称
+    pub name: String,
+    /// 实现版本
+    pub version: String,
+}
+
+/// 客户端能力声明
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientCapabilities {
+    /// 根目录能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roots: Option<ListChangedCapability>,
+    /// 采样能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<serde_json::Value>,
+    /// 信息征询能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<serde_json::Value>,
+    /// 实验性能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// 服务器能力声明
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    /// 工具能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<ListChangedCapability>,
+    /// 资源能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
+    /// 提示词能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<ListChangedCapability>,
+    /// 日志能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<serde_json::Value>,
+    /// 参数补全能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<serde_json::Value>,
+    /// 采样能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<serde_json::Value>,
+    /// 实验性能力 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// 支持列表变更通知的能力
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListChangedCapability {
+    /// 是否会发送列表变更通知
+    #[serde(default, rename = "listChanged")]
+    pub list_changed: bool,
+}
+
+/// 资源能力
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourcesCapability {
+    /// 是否支持资源订阅
+    #[serde(default)]
+    pub subscribe: bool,
+    /// 是否会发送列表变更通知
+    #[serde(default, rename = "listChanged")]
+    pub list_changed: bool,
+}
+
+/// initialize请求参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitializeParams {
+    /// 客户端请求的协议版本
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: String,
+    /// 客户端能力
+    pub capabilities: ClientCapabilities,
+    /// 客户端信息
+    #[serde(rename = "clientInfo")]
+    pub client_info: McpImplementation,
+}
+
+/// initialize响应结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitializeResult {
+    /// 服务器选定的协议版本
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: String,
+    /// 服务器能力
+    #[serde(default)]
+    pub capabilities: ServerCapabilities,
+    /// 服务器信息 (可选)
+    #[serde(default, rename = "serverInfo", skip_serializing_if = "Option::is_none")]
+    pub server_info: Option<McpServerInfo>,
+    /// 服务器使用说明 (可选)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
 /// 将JSON字符串转换为MCP消息
 pub fn parse_mcp_message(json: &str) -> Result<McpMessage, serde_json::Error> {
     serde_json::from_str(json)
@@ -146,9 +890,142 @@ pub fn serialize_mcp_message(message: &McpMessage) -> Result<String, serde_json:
 
 /// 快速创建文本内容块
 pub fn text_content(text: &str) -> McpContent {
-    McpContent {
-        content_type: "text".to_string(),
-        text: Some(text.to_string()),
-        attributes: HashMap::new(),
+    McpContent::Text {
+        text: text.to_string(),
+        annotations: None,
+    }
+}
+
+/// 快速创建图片内容块 (data为Base64编码)
+pub fn image_content(data: &str, mime_type: &str) -> McpContent {
+    McpContent::Image {
+        data: data.to_string(),
+        mime_type: mime_type.to_string(),
+        annotations: None,
+    }
+}
+
+/// 快速创建音频内容块 (data为Base64编码)
+pub fn audio_content(data: &str, mime_type: &str) -> McpContent {
+    McpContent::Audio {
+        data: data.to_string(),
+        mime_type: mime_type.to_string(),
+        annotations: None,
     }
-} 
\ No newline at end of file
+}
+
+/// 快速创建内嵌资源内容块
+pub fn resource_content(resource: McpResourceContent) -> McpContent {
+    McpContent::Resource {
+        resource,
+        annotations: None,
+    }
+}
+
+/// 快速创建资源链接内容块
+pub fn resource_link_content(uri: &str, name: &str) -> McpContent {
+    McpContent::ResourceLink {
+        uri: uri.to_string(),
+        name: name.to_string(),
+        description: None,
+        mime_type: None,
+        annotations: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn round_trip(wire: serde_json::Value) -> McpContent {
+        let content: McpContent = serde_json::from_value(wire.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&content).unwrap(), wire);
+        content
+    }
+
+    #[test]
+    fn known_content_types_round_trip() {
+        let text = round_trip(json!({
+            "type": "text",
+            "text": "hi",
+            "annotations": { "audience": ["user"], "priority": 0.5 }
+        }));
+        assert_eq!(text.as_text(), Some("hi"));
+        assert_eq!(text.annotations().and_then(|annotations| annotations.priority), Some(0.5));
+
+        let image = round_trip(json!({ "type": "image", "data": "aGk=", "mimeType": "image/png" }));
+        assert!(matches!(image, McpContent::Image { ref mime_type, .. } if mime_type == "image/png"));
+
+        let audio = round_trip(json!({ "type": "audio", "data": "aGk=", "mimeType": "audio/wav" }));
+        assert_eq!(audio.content_type(), "audio");
+
+        let resource = round_trip(json!({
+            "type": "resource",
+            "resource": { "uri": "file:///a.txt", "mimeType": "text/plain", "text": "a" }
+        }));
+        assert!(matches!(resource, McpContent::Resource { ref resource, .. } if resource.as_text() == Some("a")));
+
+        let link = round_trip(json!({
+            "type": "resource_link",
+            "uri": "file:///b.txt",
+            "name": "b",
+            "description": "file b",
+            "mimeType": "text/plain"
+        }));
+        assert_eq!(link.content_type(), "resource_link");
+    }
+
+    #[test]
+    fn unknown_content_type_is_preserved() {
+        let wire = json!({ "type": "hologram", "frames": [1, 2, 3], "nested": { "a": null } });
+
+        let content = round_trip(wire.clone());
+
+        assert_eq!(content, McpContent::Unknown(wire));
+        assert_eq!(content.content_type(), "hologram");
+        assert_eq!(content.annotations(), None);
+    }
+
+    #[test]
+    fn known_content_type_missing_fields_is_rejected() {
+        assert!(serde_json::from_value::<McpContent>(json!({ "type": "image", "data": "aGk=" })).is_err());
+        assert!(serde_json::from_value::<McpContent>(json!({ "text": "no type" })).is_err());
+    }
+
+    #[test]
+    fn blob_resource_is_base64_encoded() {
+        let content = McpResourceContent::blob("file:///a.bin", vec![0, 1, 2, 255], Some("application/x-test"));
+
+        let wire = serde_json::to_value(&content).unwrap();
+
+        assert_eq!(wire, json!({ "uri": "file:///a.bin", "mimeType": "application/x-test", "blob": "AAEC/w==" }));
+        assert_eq!(serde_json::from_value::<McpResourceContent>(wire).unwrap(), content);
+    }
+
+    #[test]
+    fn blob_resource_accepts_mime_type_alias_and_wrapped_base64() {
+        let content: McpResourceContent = serde_json::from_value(json!({
+            "uri": "file:///a.bin",
+            "mime_type": "image/png",
+            "blob": "AAEC\n/w=="
+        })).unwrap();
+
+        assert!(content.is_blob());
+        assert_eq!(content.as_blob(), Some(&[0, 1, 2, 255][..]));
+        assert_eq!(content.mime_type.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn invalid_blob_is_rejected() {
+        let result = serde_json::from_value::<McpResourceContent>(json!({ "uri": "file:///a.bin", "blob": "not base64!" }));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn effective_mime_type_defaults_by_body() {
+        assert_eq!(McpResourceContent::text("file:///a", "a", None).effective_mime_type(), "text/plain");
+        assert_eq!(McpResourceContent::blob("file:///b", vec![1], None).effective_mime_type(), "application/octet-stream");
+    }
+}
@@ -0,0 +1,218 @@
+use crate::mcp::client::McpClient;
+use crate::mcp::manager::McpClientManager;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+/// FFI使用的不透明句柄，0不是有效句柄
+pub type Handle = u64;
+
+/// 句柄指向的对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleKind {
+    Client,
+    Manager,
+}
+
+impl fmt::Display for HandleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandleKind::Client => write!(f, "客户端"),
+            HandleKind::Manager => write!(f, "管理器"),
+        }
+    }
+}
+
+/// 句柄校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandleError {
+    /// 句柄从未分配
+    Invalid(Handle),
+    /// 句柄已释放 (使用过期句柄或重复释放)
+    Released(Handle),
+    /// 句柄指向的对象类型与调用的接口不符
+    WrongKind { handle: Handle, expected: HandleKind },
+}
+
+impl HandleError {
+    /// 返回给RN的错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            HandleError::Invalid(_) => "invalid_handle",
+            HandleError::Released(_) => "handle_released",
+            HandleError::WrongKind { .. } => "wrong_handle_kind",
+        }
+    }
+
+    /// 出错的句柄
+    pub fn handle(&self) -> Handle {
+        match self {
+            HandleError::Invalid(handle) | HandleError::Released(handle) => *handle,
+            HandleError::WrongKind { handle, .. } => *handle,
+        }
+    }
+}
+
+impl fmt::Display for HandleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandleError::Invalid(handle) => write!(f, "无效的句柄: {}", handle),
+            HandleError::Released(handle) => write!(f, "句柄已释放: {}", handle),
+            HandleError::WrongKind { handle, expected } => write!(f, "句柄{}不是{}句柄", handle, expected),
+        }
+    }
+}
+
+impl Error for HandleError {}
+
+/// 句柄指向的对象
+///
+/// 对象内部自行同步，所有操作只需共享借用；对一个对象的长时间操作不会阻塞注册表和同一对象上的其他调用
+#[derive(Clone)]
+pub enum HandleEntry {
+    Client(Arc<McpClient>),
+    Manager(Arc<McpClientManager>),
+}
+
+impl HandleEntry {
+    fn kind(&self) -> HandleKind {
+        match self {
+            HandleEntry::Client(_) => HandleKind::Client,
+            HandleEntry::Manager(_) => HandleKind::Manager,
+        }
+    }
+}
+
+/// 句柄注册表
+///
+/// 句柄单调递增且不复用，因此能区分从未分配的句柄和已释放的句柄
+pub struct HandleRegistry {
+    /// 下一个分配的句柄
+    next_handle: Handle,
+    /// 有效句柄
+    entries: HashMap<Handle, HandleEntry>,
+    /// 已释放的句柄
+    released: HashSet<Handle>,
+}
+
+impl HandleRegistry {
+    pub fn new() -> Self {
+        HandleRegistry {
+            next_handle: 1,
+            entries: HashMap::new(),
+            released: HashSet::new(),
+        }
+    }
+
+    /// 登记对象并分配句柄
+    pub fn insert(&mut self, entry: HandleEntry) -> Handle {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        self.entries.insert(handle, entry);
+        handle
+    }
+
+    /// 查找句柄指向的对象
+    pub fn get(&self, handle: Handle) -> Result<HandleEntry, HandleError> {
+        match self.entries.get(&handle) {
+            Some(entry) => Ok(entry.clone()),
+            None if self.released.contains(&handle) => Err(HandleError::Released(handle)),
+            None => Err(HandleError::Invalid(handle)),
+        }
+    }
+
+    /// 查找客户端句柄
+    pub fn client(&self, handle: Handle) -> Result<Arc<McpClient>, HandleError> {
+        match self.get(handle)? {
+            HandleEntry::Client(client) => Ok(client),
+            HandleEntry::Manager(_) => Err(HandleError::WrongKind { handle, expected: HandleKind::Client }),
+        }
+    }
+
+    /// 查找管理器句柄
+    pub fn manager(&self, handle: Handle) -> Result<Arc<McpClientManager>, HandleError> {
+        match self.get(handle)? {
+            HandleEntry::Manager(manager) => Ok(manager),
+            HandleEntry::Client(_) => Err(HandleError::WrongKind { handle, expected: HandleKind::Manager }),
+        }
+    }
+
+    /// 释放句柄，返回其指向的对象
+    ///
+    /// 类型不符时不释放；之后再使用该句柄会得到`HandleError::Released`
+    pub fn remove(&mut self, handle: Handle, expected: HandleKind) -> Result<HandleEntry, HandleError> {
+        if self.get(handle)?.kind() != expected {
+            return Err(HandleError::WrongKind { handle, expected });
+        }
+
+        self.released.insert(handle);
+        self.entries.remove(&handle).ok_or(HandleError::Released(handle))
+    }
+}
+
+impl Default for HandleRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> HandleEntry {
+        HandleEntry::Client(Arc::new(McpClient::new()))
+    }
+
+    #[test]
+    fn handles_start_at_one_and_are_not_reused() {
+        let mut registry = HandleRegistry::new();
+
+        let first = registry.insert(client());
+        registry.remove(first, HandleKind::Client).unwrap();
+        let second = registry.insert(client());
+
+        assert_eq!(first, 1);
+        assert_eq!(second, 2);
+    }
+
+    #[test]
+    fn unknown_handle_is_invalid() {
+        let registry = HandleRegistry::new();
+
+        assert_eq!(registry.client(0).err(), Some(HandleError::Invalid(0)));
+        assert_eq!(registry.manager(7).err(), Some(HandleError::Invalid(7)));
+    }
+
+    #[test]
+    fn removed_handle_is_released() {
+        let mut registry = HandleRegistry::new();
+        let handle = registry.insert(client());
+
+        assert!(registry.remove(handle, HandleKind::Client).is_ok());
+
+        assert_eq!(registry.client(handle).err(), Some(HandleError::Released(handle)));
+        assert_eq!(registry.remove(handle, HandleKind::Client).err(), Some(HandleError::Released(handle)));
+    }
+
+    #[test]
+    fn wrong_kind_is_rejected_without_releasing() {
+        let mut registry = HandleRegistry::new();
+        let handle = registry.insert(HandleEntry::Manager(Arc::new(McpClientManager::new())));
+
+        let wrong_kind = HandleError::WrongKind { handle, expected: HandleKind::Client };
+        assert_eq!(registry.client(handle).err(), Some(wrong_kind.clone()));
+        assert_eq!(registry.remove(handle, HandleKind::Client).err(), Some(wrong_kind));
+        assert!(registry.manager(handle).is_ok());
+    }
+
+    #[test]
+    fn error_codes_match_variants() {
+        assert_eq!(HandleError::Invalid(1).code(), "invalid_handle");
+        assert_eq!(HandleError::Released(2).code(), "handle_released");
+        let wrong_kind = HandleError::WrongKind { handle: 3, expected: HandleKind::Manager };
+        assert_eq!(wrong_kind.code(), "wrong_handle_kind");
+        assert_eq!(wrong_kind.handle(), 3);
+    }
+}
@@ -0,0 +1,530 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// 一条参数校验错误
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// 出错位置 (JSON Pointer格式，根为空字符串)
+    pub path: String,
+    /// 错误说明
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// 按JSON Schema校验值，返回所有校验错误
+///
+/// 支持工具参数和表单中常见的子集：type、required、properties、additionalProperties、
+/// items、enum、const、字符串长度、数值范围以及date-time/date/time/email/uri/uuid格式。
+/// 不认识的关键字会被忽略
+pub fn validate(schema: &Value, value: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_at(schema, value, "", &mut violations);
+    violations
+}
+
+fn violation(violations: &mut Vec<SchemaViolation>, path: &str, message: String) {
+    violations.push(SchemaViolation {
+        path: path.to_string(),
+        message,
+    });
+}
+
+/// 按JSON Pointer规则转义路径片段
+fn child_path(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        // true表示接受任意值，false表示拒绝任意值
+        Value::Bool(false) => return violation(violations, path, "不允许该值".to_string()),
+        _ => return,
+    };
+
+    if let Some(expected) = schema.get("type") {
+        if !matches_type(expected, value) {
+            return violation(
+                violations,
+                path,
+                format!("类型应为{}，实际为{}", describe_type(expected), type_name(value)),
+            );
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            violation(violations, path, format!("取值应为{}之一", Value::Array(options.clone())));
+        }
+    }
+
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            violation(violations, path, format!("取值应为{}", constant));
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path, violations),
+        Value::Array(items) => validate_array(schema, items, path, violations),
+        Value::String(text) => validate_string(schema, text, path, violations),
+        Value::Number(_) => validate_number(schema, value, path, violations),
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                violation(violations, &child_path(path, key), "缺少必填字段".to_string());
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, item) in object {
+        let item_path = child_path(path, key);
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(item_schema) => validate_at(item_schema, item, &item_path, violations),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => violation(violations, &item_path, "不允许的字段".to_string()),
+                Some(additional) => validate_at(additional, item, &item_path, violations),
+                None => {}
+            },
+        }
+    }
+}
+
+fn validate_array(schema: &Map<String, Value>, items: &[Value], path: &str, violations: &mut Vec<SchemaViolation>) {
+    if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+        if (items.len() as u64) < min {
+            violation(violations, path, format!("至少需要{}项", min));
+        }
+    }
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        if (items.len() as u64) > max {
+            violation(violations, path, format!("最多允许{}项", max));
+        }
+    }
+
+    if let Some(item_schema) = schema.get("items") {
+        for (index, item) in items.iter().enumerate() {
+            validate_at(item_schema, item, &child_path(path, &index.to_string()), violations);
+        }
+    }
+}
+
+fn validate_string(schema: &Map<String, Value>, text: &str, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let length = text.chars().count() as u64;
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if length < min {
+            violation(violations, path, format!("长度不能少于{}", min));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if length > max {
+            violation(violations, path, format!("长度不能超过{}", max));
+        }
+    }
+
+    if let Some(format) = schema.get("format").and_then(Value::as_str) {
+        let valid = match format {
+            "date-time" => is_date_time(text),
+            "date" => is_date(text),
+            "time" => is_time(text),
+            "email" => is_email(text),
+            "uri" => is_uri(text),
+            "uuid" => uuid::Uuid::parse_str(text).is_ok(),
+            // 不认识的格式不做校验
+            _ => true,
+        };
+        if !valid {
+            violation(violations, path, format!("不是有效的{}格式", format));
+        }
+    }
+}
+
+fn validate_number(schema: &Map<String, Value>, value: &Value, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let number = match value.as_f64() {
+        Some(number) => number,
+        None => return,
+    };
+
+    let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+    if let Some(min) = bound("minimum") {
+        if number < min {
+            violation(violations, path, format!("不能小于{}", min));
+        }
+    }
+    if let Some(max) = bound("maximum") {
+        if number > max {
+            violation(violations, path, format!("不能大于{}", max));
+        }
+    }
+    if let Some(min) = bound("exclusiveMinimum") {
+        if number <= min {
+            violation(violations, path, format!("必须大于{}", min));
+        }
+    }
+    if let Some(max) = bound("exclusiveMaximum") {
+        if number >= max {
+            violation(violations, path, format!("必须小于{}", max));
+        }
+    }
+}
+
+// ===== 类型判断 =====
+
+fn matches_type(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => matches_type_name(name, value),
+        Value::Array(names) => names.iter().filter_map(Value::as_str).any(|name| matches_type_name(name, value)),
+        _ => true,
+    }
+}
+
+fn matches_type_name(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        // 1.0这类没有小数部分的数也视为整数
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::String(name) => name.clone(),
+        Value::Array(names) => names.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("或"),
+        other => other.to_string(),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// ===== 格式校验 =====
+
+/// 解析固定位数的十进制数字
+fn parse_digits(text: &str) -> Option<u32> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// RFC 3339 full-date: YYYY-MM-DD
+fn is_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('-').collect();
+    if parts.len() != 3 || parts[0].len() != 4 || parts[1].len() != 2 || parts[2].len() != 2 {
+        return false;
+    }
+
+    let (year, month, day) = match (parse_digits(parts[0]), parse_digits(parts[1]), parse_digits(parts[2])) {
+        (Some(year), Some(month), Some(day)) => (year, month, day),
+        _ => return false,
+    };
+
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
+
+/// RFC 3339 full-time: HH:MM:SS[.frac](Z|±HH:MM)
+fn is_time(text: &str) -> bool {
+    let (partial, offset) = if let Some(partial) = text.strip_suffix(['Z', 'z']) {
+        (partial, None)
+    } else {
+        match text.rfind(['+', '-']) {
+            Some(pos) => (&text[..pos], Some(&text[pos + 1..])),
+            None => return false,
+        }
+    };
+
+    if let Some(offset) = offset {
+        match offset.split_once(':') {
+            Some((hours, minutes)) if hours.len() == 2 && minutes.len() == 2 => {
+                match (parse_digits(hours), parse_digits(minutes)) {
+                    (Some(hours), Some(minutes)) if hours < 24 && minutes < 60 => {}
+                    _ => return false,
+                }
+            }
+            _ => return false,
+        }
+    }
+
+    let (clock, fraction) = match partial.split_once('.') {
+        Some((clock, fraction)) => (clock, Some(fraction)),
+        None => (partial, None),
+    };
+    if let Some(fraction) = fraction {
+        if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return false;
+        }
+    }
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    if parts.len() != 3 || parts.iter().any(|part| part.len() != 2) {
+        return false;
+    }
+
+    match (parse_digits(parts[0]), parse_digits(parts[1]), parse_digits(parts[2])) {
+        // 允许闰秒
+        (Some(hours), Some(minutes), Some(seconds)) => hours < 24 && minutes < 60 && seconds <= 60,
+        _ => false,
+    }
+}
+
+/// RFC 3339 date-time: full-date "T" full-time
+fn is_date_time(text: &str) -> bool {
+    match text.find(['T', 't', ' ']) {
+        Some(pos) => is_date(&text[..pos]) && is_time(&text[pos + 1..]),
+        None => false,
+    }
+}
+
+fn is_email(text: &str) -> bool {
+    match text.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !text.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_uri(text: &str) -> bool {
+    match text.split_once(':') {
+        Some((scheme, rest)) => {
+            let mut chars = scheme.chars();
+            chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+                && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                && !rest.is_empty()
+                && !text.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// 检查elicitation请求的schema是否符合协议限制
+///
+/// 只允许顶层为object、属性均为string/number/integer/boolean的扁平结构，
+/// 字符串仅支持email、uri、date、date-time格式
+pub fn check_restricted_schema(schema: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+
+    if schema.get("type").and_then(Value::as_str) != Some("object") {
+        violation(&mut violations, "", "顶层类型必须为object".to_string());
+    }
+
+    let properties = match schema.get("properties") {
+        Some(Value::Object(properties)) => properties,
+        Some(_) => {
+            violation(&mut violations, "/properties", "properties必须为对象".to_string());
+            return violations;
+        }
+        None => return violations,
+    };
+
+    for (name, property) in properties {
+        let path = child_path("/properties", name);
+        match property.get("type").and_then(Value::as_str) {
+            Some("string") => {
+                if let Some(format) = property.get("format").and_then(Value::as_str) {
+                    if !matches!(format, "email" | "uri" | "date" | "date-time") {
+                        violation(&mut violations, &path, format!("不支持的字符串格式: {}", format));
+                    }
+                }
+                if let Some(options) = property.get("enum") {
+                    if !options.as_array().is_some_and(|options| options.iter().all(Value::is_string)) {
+                        violation(&mut violations, &path, "enum必须为字符串数组".to_string());
+                    }
+                }
+            }
+            Some("number" | "integer" | "boolean") => {}
+            Some(other) => violation(&mut violations, &path, format!("不支持的属性类型: {}", other)),
+            None => violation(&mut violations, &path, "缺少type".to_string()),
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 返回校验错误的位置列表
+    fn violation_paths(schema: Value, value: Value) -> Vec<String> {
+        validate(&schema, &value).into_iter().map(|violation| violation.path).collect()
+    }
+
+    #[test]
+    fn checks_types() {
+        assert!(validate(&json!({ "type": "string" }), &json!("text")).is_empty());
+        assert!(validate(&json!({ "type": ["string", "null"] }), &json!(null)).is_empty());
+        assert!(validate(&json!({ "type": "integer" }), &json!(3.0)).is_empty());
+
+        let violations = validate(&json!({ "type": "integer" }), &json!(3.5));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message, "类型应为integer，实际为number");
+
+        // 类型不符时不再检查其他关键字
+        assert_eq!(validate(&json!({ "type": "string", "minLength": 5 }), &json!(1)).len(), 1);
+    }
+
+    #[test]
+    fn reports_missing_required_fields() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name", "a/b"],
+            "properties": { "name": { "type": "string" } },
+        });
+
+        assert!(validate(&schema, &json!({ "name": "x", "a/b": 1 })).is_empty());
+        assert_eq!(violation_paths(schema, json!({})), vec!["/name", "/a~1b"]);
+    }
+
+    #[test]
+    fn checks_enum_and_const() {
+        let schema = json!({ "enum": ["red", "green"] });
+        assert!(validate(&schema, &json!("red")).is_empty());
+        assert_eq!(validate(&schema, &json!("blue")).len(), 1);
+
+        let schema = json!({ "const": 42 });
+        assert!(validate(&schema, &json!(42)).is_empty());
+        assert_eq!(validate(&schema, &json!(41))[0].message, "取值应为42");
+    }
+
+    #[test]
+    fn checks_string_formats() {
+        let valid = [
+            ("date-time", "2024-02-29T12:30:00Z"),
+            ("date-time", "2024-01-01T00:00:00.123+08:00"),
+            ("date", "2000-02-29"),
+            ("time", "23:59:60Z"),
+            ("email", "user@example.com"),
+            ("uri", "https://example.com/a"),
+            ("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            ("unknown-format", "anything"),
+        ];
+        for (format, text) in valid {
+            assert!(validate(&json!({ "format": format }), &json!(text)).is_empty(), "{} {}", format, text);
+        }
+
+        let invalid = [
+            ("date-time", "2024-01-01"),
+            ("date", "2023-02-29"),
+            ("date", "2024-13-01"),
+            ("time", "24:00:00Z"),
+            ("time", "12:00:00"),
+            ("email", "user@localhost"),
+            ("email", "a b@example.com"),
+            ("uri", "no-scheme"),
+            ("uuid", "not-a-uuid"),
+        ];
+        for (format, text) in invalid {
+            assert_eq!(validate(&json!({ "format": format }), &json!(text)).len(), 1, "{} {}", format, text);
+        }
+    }
+
+    #[test]
+    fn checks_ranges() {
+        let schema = json!({ "minimum": 1, "maximum": 10 });
+        assert!(validate(&schema, &json!(1)).is_empty());
+        assert!(validate(&schema, &json!(10)).is_empty());
+        assert_eq!(validate(&schema, &json!(0))[0].message, "不能小于1");
+        assert_eq!(validate(&schema, &json!(11))[0].message, "不能大于10");
+
+        let schema = json!({ "exclusiveMinimum": 0, "exclusiveMaximum": 1 });
+        assert!(validate(&schema, &json!(0.5)).is_empty());
+        assert_eq!(validate(&schema, &json!(0)).len(), 1);
+        assert_eq!(validate(&schema, &json!(1)).len(), 1);
+
+        // 字符串长度按字符计算
+        let schema = json!({ "minLength": 2, "maxLength": 3 });
+        assert!(validate(&schema, &json!("你好")).is_empty());
+        assert_eq!(validate(&schema, &json!("a")).len(), 1);
+        assert_eq!(validate(&schema, &json!("abcd")).len(), 1);
+
+        let schema = json!({ "minItems": 1, "maxItems": 2, "items": { "type": "number" } });
+        assert_eq!(validate(&schema, &json!([])).len(), 1);
+        assert_eq!(violation_paths(schema, json!([1, "x"])), vec!["/1"]);
+    }
+
+    #[test]
+    fn checks_additional_properties() {
+        let properties = json!({ "name": { "type": "string" } });
+
+        let schema = json!({ "properties": properties, "additionalProperties": false });
+        assert!(validate(&schema, &json!({ "name": "x" })).is_empty());
+        assert_eq!(violation_paths(schema, json!({ "name": "x", "extra": 1 })), vec!["/extra"]);
+
+        let schema = json!({ "properties": properties, "additionalProperties": { "type": "integer" } });
+        assert!(validate(&schema, &json!({ "extra": 1 })).is_empty());
+        assert_eq!(violation_paths(schema, json!({ "extra": "x" })), vec!["/extra"]);
+
+        // 未声明时允许任意额外字段
+        let schema = json!({ "properties": properties });
+        assert!(validate(&schema, &json!({ "extra": [1, 2] })).is_empty());
+    }
+
+    #[test]
+    fn restricted_schema_rejects_nested_properties() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "format": "email" },
+                "age": { "type": "integer" },
+                "address": { "type": "object" },
+                "color": { "type": "string", "enum": ["red", 1] },
+            },
+        });
+
+        let paths: Vec<String> = check_restricted_schema(&schema).into_iter().map(|v| v.path).collect();
+        assert_eq!(paths, vec!["/properties/address", "/properties/color"]);
+        assert_eq!(check_restricted_schema(&json!({ "type": "array" })).len(), 1);
+    }
+}
@@ -0,0 +1,713 @@
+use crate::mcp::client::McpError;
+use crate::mcp::transport::Transport;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, Url};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+use tokio::task::JoinHandle;
+
+type Result<T> = std::result::Result<T, McpError>;
+
+/// 会话ID请求/响应头
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+/// 断点续传请求头
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// HTTP传输配置
+#[derive(Debug, Clone)]
+pub struct HttpTransportConfig {
+    /// 消息端点 (POST请求地址)
+    pub url: String,
+    /// 旧版HTTP+SSE模式的事件流地址，设置后先从该地址获取消息端点
+    pub sse_endpoint: Option<String>,
+    /// 每个请求附带的额外请求头 (如认证令牌)
+    pub headers: HashMap<String, String>,
+    /// 是否打开独立的GET事件流以接收服务器主动推送的消息
+    pub open_event_stream: bool,
+    /// 事件流断开后的最大恢复次数
+    pub max_resume_attempts: usize,
+    /// 事件流恢复前的默认等待时间 (服务器可通过retry字段覆盖)
+    pub resume_delay: Duration,
+}
+
+impl HttpTransportConfig {
+    /// 创建新的HTTP传输配置
+    pub fn new(url: &str) -> Self {
+        HttpTransportConfig {
+            url: url.to_string(),
+            sse_endpoint: None,
+            headers: HashMap::new(),
+            open_event_stream: true,
+            max_resume_attempts: 5,
+            resume_delay: Duration::from_millis(1000),
+        }
+    }
+}
+
+/// 基于HTTP POST和Server-Sent Events的传输
+///
+/// 请求通过POST发送，服务器可以直接返回JSON，也可以返回SSE流逐条推送消息；
+/// 会话ID在请求间保持，事件流断开后使用`Last-Event-ID`恢复
+pub struct HttpTransport {
+    /// 与后台任务共享的状态
+    shared: Arc<HttpShared>,
+    /// 收到的消息队列，以错误结束时receive返回该错误
+    inbound: tokio::sync::Mutex<mpsc::UnboundedReceiver<Result<String>>>,
+}
+
+/// HTTP传输的共享状态
+struct HttpShared {
+    /// HTTP客户端
+    client: reqwest::Client,
+    /// 传输配置
+    config: HttpTransportConfig,
+    /// 当前消息端点
+    post_url: Mutex<Url>,
+    /// 服务器分配的会话ID
+    session_id: Mutex<Option<String>>,
+    /// 服务器建议的重连等待时间
+    retry_delay: Mutex<Option<Duration>>,
+    /// 消息队列发送端 (关闭后为None)
+    inbound: Mutex<Option<mpsc::UnboundedSender<Result<String>>>>,
+    /// 独立事件流是否已启动
+    event_stream_started: AtomicBool,
+    /// 旧版模式下收到消息端点的通知
+    endpoint_ready: Notify,
+    /// 后台任务
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl HttpTransport {
+    /// 创建HTTP传输
+    ///
+    /// 旧版HTTP+SSE模式下会等待服务器通过`endpoint`事件告知消息端点
+    pub async fn connect(config: HttpTransportConfig) -> Result<Self> {
+        let post_url = Url::parse(&config.url)
+            .map_err(|e| McpError::ConnectionError(format!("无效的服务器地址 {}: {}", config.url, e)))?;
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let shared = Arc::new(HttpShared {
+            client: reqwest::Client::new(),
+            config,
+            post_url: Mutex::new(post_url),
+            session_id: Mutex::new(None),
+            retry_delay: Mutex::new(None),
+            inbound: Mutex::new(Some(tx)),
+            event_stream_started: AtomicBool::new(false),
+            endpoint_ready: Notify::new(),
+            tasks: Mutex::new(Vec::new()),
+        });
+
+        if let Some(sse_endpoint) = shared.config.sse_endpoint.clone() {
+            let sse_url = Url::parse(&sse_endpoint)
+                .map_err(|e| McpError::ConnectionError(format!("无效的事件流地址 {}: {}", sse_endpoint, e)))?;
+
+            shared.event_stream_started.store(true, Ordering::SeqCst);
+            let endpoint_ready = shared.endpoint_ready.notified();
+            HttpShared::spawn(&shared, HttpShared::run_event_stream(shared.clone(), sse_url));
+
+            tokio::time::timeout(Duration::from_secs(30), endpoint_ready).await
+                .map_err(|_| McpError::ConnectionError("等待服务器消息端点超时".to_string()))?;
+        }
+
+        Ok(HttpTransport {
+            shared,
+            inbound: tokio::sync::Mutex::new(rx),
+        })
+    }
+}
+
+impl HttpShared {
+    /// 在后台运行任务并记录句柄
+    fn spawn<F>(shared: &Arc<HttpShared>, future: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        shared.tasks.lock().unwrap().push(tokio::spawn(future));
+    }
+
+    /// 附加自定义请求头和会话ID
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        let mut builder = self.client.request(method, url);
+        for (name, value) in &self.config.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        if let Some(session_id) = self.session_id.lock().unwrap().as_ref() {
+            builder = builder.header(SESSION_ID_HEADER, session_id.as_str());
+        }
+        builder
+    }
+
+    /// 记录服务器返回的会话ID
+    fn capture_session(&self, response: &Response) {
+        if let Some(session_id) = response.headers().get(SESSION_ID_HEADER).and_then(|v| v.to_str().ok()) {
+            *self.session_id.lock().unwrap() = Some(session_id.to_string());
+        }
+    }
+
+    /// 将消息放入接收队列
+    fn push(&self, frame: String) {
+        if let Some(inbound) = self.inbound.lock().unwrap().as_ref() {
+            let _ = inbound.send(Ok(frame));
+        }
+    }
+
+    /// 以错误结束接收队列，之后的发送都会失败，后台事件流随之停止
+    fn fail(&self, error: McpError) {
+        if let Some(inbound) = self.inbound.lock().unwrap().take() {
+            let _ = inbound.send(Err(error));
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inbound.lock().unwrap().is_none()
+    }
+
+    /// 将JSON响应体放入接收队列 (支持批量数组)
+    fn push_json_body(&self, body: &str) -> Result<()> {
+        let value: Value = serde_json::from_str(body)
+            .map_err(|e| McpError::ProtocolError(format!("解析HTTP响应失败: {}", e)))?;
+        match value {
+            Value::Array(items) => items.into_iter().for_each(|item| self.push(item.to_string())),
+            Value::Null => {}
+            item => self.push(item.to_string()),
+        }
+        Ok(())
+    }
+
+    /// 处理SSE事件，记录所在事件流最近一次收到的事件ID
+    fn handle_event(&self, event: SseEvent, last_event_id: &mut Option<String>) {
+        if let Some(id) = event.id {
+            *last_event_id = Some(id);
+        }
+        if let Some(retry) = event.retry {
+            *self.retry_delay.lock().unwrap() = Some(Duration::from_millis(retry));
+        }
+
+        match event.event.as_deref() {
+            // 旧版HTTP+SSE模式：服务器告知消息端点
+            Some("endpoint") => {
+                let base = self.post_url.lock().unwrap().clone();
+                match base.join(event.data.trim()) {
+                    Ok(url) => {
+                        *self.post_url.lock().unwrap() = url;
+                        self.endpoint_ready.notify_one();
+                    }
+                    Err(e) => log::error!("无效的消息端点 {}: {}", event.data, e),
+                }
+            }
+            None | Some("message") if !event.data.is_empty() => self.push(event.data),
+            _ => {}
+        }
+    }
+
+    /// 读取SSE响应流直到结束，返回流是否正常结束
+    ///
+    /// 事件ID只在所属的流内有效，每个流各自记录，恢复时只用于该流
+    async fn consume_stream(&self, response: Response, last_event_id: &mut Option<String>) -> bool {
+        let mut parser = SseParser::default();
+        let mut body = response.bytes_stream();
+
+        while let Some(chunk) = body.next().await {
+            match chunk {
+                Ok(bytes) => {
+                    for event in parser.feed(&bytes) {
+                        self.handle_event(event, last_event_id);
+                    }
+                }
+                Err(e) => {
+                    log::debug!("事件流中断: {}", e);
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// 使用GET请求打开事件流，携带`Last-Event-ID`以恢复
+    async fn open_stream(&self, url: Url, last_event_id: Option<&str>) -> std::result::Result<Option<Response>, String> {
+        let mut builder = self.request(Method::GET, url).header(ACCEPT, "text/event-stream");
+        if let Some(last_event_id) = last_event_id {
+            builder = builder.header(LAST_EVENT_ID_HEADER, last_event_id);
+        }
+
+        let had_session = self.session_id.lock().unwrap().is_some();
+        let response = builder.send().await.map_err(|e| e.to_string())?;
+        self.capture_session(&response);
+
+        match response.status() {
+            // 服务器不提供独立事件流
+            StatusCode::METHOD_NOT_ALLOWED => Ok(None),
+            StatusCode::NOT_FOUND if had_session => {
+                self.fail(McpError::ConnectionError("服务器会话已过期".to_string()));
+                Ok(None)
+            }
+            status if status.is_success() => Ok(Some(response)),
+            status => Err(format!("HTTP {}", status)),
+        }
+    }
+
+    /// 等待一段时间后再尝试恢复事件流
+    async fn wait_before_resume(&self) {
+        let delay = self.retry_delay.lock().unwrap().unwrap_or(self.config.resume_delay);
+        tokio::time::sleep(delay).await;
+    }
+
+    /// 持续读取GET事件流，断开后自动恢复
+    async fn run_event_stream(shared: Arc<HttpShared>, url: Url) {
+        let mut attempts = 0;
+        let mut last_event_id = None;
+
+        while !shared.is_closed() && attempts <= shared.config.max_resume_attempts {
+            match shared.open_stream(url.clone(), last_event_id.as_deref()).await {
+                Ok(Some(response)) => {
+                    attempts = 0;
+                    shared.consume_stream(response, &mut last_event_id).await;
+                }
+                Ok(None) => return,
+                Err(e) => {
+                    log::warn!("打开事件流失败: {}", e);
+                    attempts += 1;
+                }
+            }
+
+            shared.wait_before_resume().await;
+        }
+    }
+
+    /// 读取POST返回的SSE流，中断时通过GET请求恢复
+    async fn run_response_stream(shared: Arc<HttpShared>, response: Response) {
+        let mut last_event_id = None;
+        if shared.consume_stream(response, &mut last_event_id).await {
+            return;
+        }
+        // 流中没有事件ID时服务器无法定位断点，不能恢复
+        if last_event_id.is_none() {
+            return;
+        }
+
+        let url = shared.post_url.lock().unwrap().clone();
+        for _ in 0..shared.config.max_resume_attempts {
+            if shared.is_closed() {
+                return;
+            }
+            shared.wait_before_resume().await;
+
+            match shared.open_stream(url.clone(), last_event_id.as_deref()).await {
+                Ok(Some(response)) => {
+                    if shared.consume_stream(response, &mut last_event_id).await {
+                        return;
+                    }
+                }
+                Ok(None) => return,
+                Err(e) => log::warn!("恢复事件流失败: {}", e),
+            }
+        }
+    }
+
+    /// 在获得会话后启动独立事件流
+    fn ensure_event_stream(shared: &Arc<HttpShared>) {
+        if !shared.config.open_event_stream || shared.event_stream_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let url = shared.post_url.lock().unwrap().clone();
+        HttpShared::spawn(shared, HttpShared::run_event_stream(shared.clone(), url));
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, frame: String) -> Result<()> {
+        let shared = &self.shared;
+        if shared.is_closed() {
+            return Err(McpError::Disconnected);
+        }
+
+        let url = shared.post_url.lock().unwrap().clone();
+        let had_session = shared.session_id.lock().unwrap().is_some();
+        let response = shared.request(Method::POST, url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(ACCEPT, "application/json, text/event-stream")
+            .body(frame)
+            .send()
+            .await
+            .map_err(|e| McpError::ConnectionError(format!("HTTP请求失败: {}", e)))?;
+        shared.capture_session(&response);
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND && had_session {
+            // 会话过期后该传输无法继续使用，通过接收端结束连接，由客户端决定是否重连
+            shared.fail(McpError::ConnectionError("服务器会话已过期".to_string()));
+            return Err(McpError::ConnectionError("服务器会话已过期".to_string()));
+        }
+        if !status.is_success() {
+            return Err(McpError::CommunicationError(format!("服务器返回HTTP {}", status)));
+        }
+
+        let content_type = response.headers().get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        if status == StatusCode::ACCEPTED {
+            // 通知和响应无需返回内容
+        } else if content_type.starts_with("text/event-stream") {
+            HttpShared::spawn(shared, HttpShared::run_response_stream(shared.clone(), response));
+        } else {
+            let body = response.text().await
+                .map_err(|e| McpError::CommunicationError(format!("读取HTTP响应失败: {}", e)))?;
+            if !body.trim().is_empty() {
+                shared.push_json_body(&body)?;
+            }
+        }
+
+        HttpShared::ensure_event_stream(shared);
+        Ok(())
+    }
+
+    async fn receive(&self) -> Result<Option<String>> {
+        self.inbound.lock().await.recv().await.transpose()
+    }
+
+    async fn close(&self) -> Result<()> {
+        let shared = &self.shared;
+        shared.inbound.lock().unwrap().take();
+        for task in shared.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+
+        // 通知服务器结束会话 (尽力而为)
+        if shared.session_id.lock().unwrap().is_some() {
+            let url = shared.post_url.lock().unwrap().clone();
+            let _ = shared.request(Method::DELETE, url).send().await;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        for task in self.shared.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+}
+
+// ===== SSE解析 =====
+
+/// 一条Server-Sent Event
+#[derive(Debug, Default)]
+struct SseEvent {
+    /// 事件类型
+    event: Option<String>,
+    /// 事件数据 (多行以换行连接)
+    data: String,
+    /// 事件ID
+    id: Option<String>,
+    /// 建议的重连等待时间 (毫秒)
+    retry: Option<u64>,
+}
+
+/// 增量SSE解析器
+#[derive(Default)]
+struct SseParser {
+    /// 尚未构成完整行的字节
+    buffer: Vec<u8>,
+    /// 上一段以CR结尾，下一段开头的LF属于同一个行尾
+    skip_lf: bool,
+    /// 正在组装的事件
+    current: SseEvent,
+    /// 当前事件是否包含字段
+    has_fields: bool,
+}
+
+impl SseParser {
+    /// 取出缓冲区中的下一行，行尾可以是CRLF、LF或单独的CR
+    fn next_line(&mut self) -> Option<String> {
+        if self.skip_lf && !self.buffer.is_empty() {
+            if self.buffer[0] == b'\n' {
+                self.buffer.remove(0);
+            }
+            self.skip_lf = false;
+        }
+
+        let pos = self.buffer.iter().position(|&b| b == b'\n' || b == b'\r')?;
+        let line = String::from_utf8_lossy(&self.buffer[..pos]).into_owned();
+        let mut end = pos + 1;
+        if self.buffer[pos] == b'\r' {
+            match self.buffer.get(end) {
+                Some(b'\n') => end += 1,
+                Some(_) => {}
+                // CR在段末尾，LF可能在下一段
+                None => self.skip_lf = true,
+            }
+        }
+        self.buffer.drain(..end);
+        Some(line)
+    }
+
+    /// 输入一段字节，返回其中完整的事件
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+
+        while let Some(line) = self.next_line() {
+            let line = line.as_str();
+
+            if line.is_empty() {
+                if self.has_fields {
+                    let mut event = std::mem::take(&mut self.current);
+                    if event.data.ends_with('\n') {
+                        event.data.pop();
+                    }
+                    events.push(event);
+                    self.has_fields = false;
+                }
+                continue;
+            }
+
+            // 注释行
+            if line.starts_with(':') {
+                continue;
+            }
+
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+
+            self.has_fields = true;
+            match field {
+                "event" => self.current.event = Some(value.to_string()),
+                "data" => {
+                    self.current.data.push_str(value);
+                    self.current.data.push('\n');
+                }
+                "id" => self.current.id = Some(value.to_string()),
+                "retry" => self.current.retry = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    fn parse(chunks: &[&str]) -> Vec<SseEvent> {
+        let mut parser = SseParser::default();
+        chunks.iter().flat_map(|chunk| parser.feed(chunk.as_bytes())).collect()
+    }
+
+    #[test]
+    fn joins_multi_line_data() {
+        let events = parse(&["data: first\ndata: second\n\n"]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "first\nsecond");
+    }
+
+    #[test]
+    fn reads_event_id_and_retry_fields() {
+        let events = parse(&["event: message\nid: 7\nretry: 250\ndata: {}\n\n"]);
+        assert_eq!(events[0].event.as_deref(), Some("message"));
+        assert_eq!(events[0].id.as_deref(), Some("7"));
+        assert_eq!(events[0].retry, Some(250));
+        assert_eq!(events[0].data, "{}");
+    }
+
+    #[test]
+    fn skips_comment_lines() {
+        let events = parse(&[": keepalive\n\n", ":note\ndata: x\n\n"]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "x");
+    }
+
+    #[test]
+    fn accepts_crlf_and_bare_cr_line_endings() {
+        let events = parse(&["data: a\r\n\r\n", "data: b\r\rdata: c\r\n\n"]);
+        let data: Vec<&str> = events.iter().map(|event| event.data.as_str()).collect();
+        assert_eq!(data, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn crlf_split_across_chunks_is_one_line_ending() {
+        // 若把第二段开头的LF当作空行，会提前分发只含a的事件
+        let events = parse(&["data: a\r", "\ndata: b\r\n", "\r\n"]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "a\nb");
+    }
+
+    #[test]
+    fn buffers_partial_lines_between_chunks() {
+        let events = parse(&["da", "ta: {\"n\"", ":1}\n", "\n"]);
+        assert_eq!(events[0].data, "{\"n\":1}");
+    }
+
+    /// 进程内服务器收到的请求
+    #[derive(Debug, Clone)]
+    struct Request {
+        method: String,
+        headers: HashMap<String, String>,
+    }
+
+    /// 启动进程内HTTP服务器，每个连接处理一个请求，handler返回写回后即关闭连接的原始响应
+    async fn spawn_server<F>(mut handler: F) -> (String, mpsc::UnboundedReceiver<Request>)
+    where
+        F: FnMut(&Request) -> String + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let request = match read_request(&mut stream).await {
+                    Some(request) => request,
+                    None => continue,
+                };
+                let response = handler(&request);
+                let _ = tx.send(request);
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        (format!("http://{}/mcp", address), rx)
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+        let mut data = Vec::new();
+        let mut buf = [0u8; 4096];
+        let header_end = loop {
+            if let Some(pos) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+                break pos;
+            }
+            let n = stream.read(&mut buf).await.ok()?;
+            if n == 0 {
+                return None;
+            }
+            data.extend_from_slice(&buf[..n]);
+        };
+
+        let head = String::from_utf8_lossy(&data[..header_end]).into_owned();
+        let mut lines = head.split("\r\n");
+        let method = lines.next()?.split(' ').next()?.to_string();
+        let headers: HashMap<String, String> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+            .collect();
+
+        // 读完请求体，避免关闭连接时客户端收到RST
+        let length: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+        let mut received = data.len() - header_end - 4;
+        while received < length {
+            let n = stream.read(&mut buf).await.ok()?;
+            if n == 0 {
+                break;
+            }
+            received += n;
+        }
+
+        Some(Request { method, headers })
+    }
+
+    fn json_response(status: &str, extra_headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n{}\r\n{}",
+            status, body.len(), extra_headers, body,
+        )
+    }
+
+    /// 分块传输的SSE响应，complete为false时不发送结束块，模拟中途断开的流
+    fn sse_response(events: &str, complete: bool) -> String {
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\nconnection: close\r\n\r\n{:x}\r\n{}\r\n",
+            events.len(), events,
+        );
+        if complete {
+            response.push_str("0\r\n\r\n");
+        }
+        response
+    }
+
+    fn config(url: &str) -> HttpTransportConfig {
+        HttpTransportConfig {
+            open_event_stream: false,
+            resume_delay: Duration::from_millis(10),
+            ..HttpTransportConfig::new(url)
+        }
+    }
+
+    async fn receive(transport: &HttpTransport) -> Result<Option<String>> {
+        tokio::time::timeout(Duration::from_secs(2), transport.receive()).await.expect("等待消息超时")
+    }
+
+    #[tokio::test]
+    async fn tracks_session_id_and_resumes_stream_with_last_event_id() {
+        let mut posts = 0;
+        let (url, mut requests) = spawn_server(move |request| match request.method.as_str() {
+            "POST" => {
+                posts += 1;
+                if posts == 1 {
+                    json_response("200 OK", "mcp-session-id: session-1\r\n", "{\"id\":1}")
+                } else {
+                    sse_response("retry: 10\nid: 41\ndata: {\"n\":1}\n\n", false)
+                }
+            }
+            _ => sse_response("id: 42\ndata: {\"n\":2}\n\n", true),
+        }).await;
+
+        let transport = HttpTransport::connect(config(&url)).await.unwrap();
+        transport.send("{\"id\":1}".to_string()).await.unwrap();
+        assert_eq!(receive(&transport).await.unwrap().as_deref(), Some("{\"id\":1}"));
+
+        transport.send("{\"id\":2}".to_string()).await.unwrap();
+        assert_eq!(receive(&transport).await.unwrap().as_deref(), Some("{\"n\":1}"));
+        assert_eq!(receive(&transport).await.unwrap().as_deref(), Some("{\"n\":2}"));
+
+        let first = requests.recv().await.unwrap();
+        assert!(!first.headers.contains_key(SESSION_ID_HEADER));
+
+        let second = requests.recv().await.unwrap();
+        assert_eq!(second.headers.get(SESSION_ID_HEADER).map(String::as_str), Some("session-1"));
+
+        let resume = requests.recv().await.unwrap();
+        assert_eq!(resume.method, "GET");
+        assert_eq!(resume.headers.get(SESSION_ID_HEADER).map(String::as_str), Some("session-1"));
+        assert_eq!(resume.headers.get(LAST_EVENT_ID_HEADER).map(String::as_str), Some("41"));
+    }
+
+    #[tokio::test]
+    async fn expired_session_ends_receive() {
+        let mut posts = 0;
+        let (url, _requests) = spawn_server(move |_| {
+            posts += 1;
+            if posts == 1 {
+                json_response("200 OK", "mcp-session-id: session-1\r\n", "{\"id\":1}")
+            } else {
+                json_response("404 Not Found", "", "")
+            }
+        }).await;
+
+        let transport = HttpTransport::connect(config(&url)).await.unwrap();
+        transport.send("{\"id\":1}".to_string()).await.unwrap();
+        assert_eq!(receive(&transport).await.unwrap().as_deref(), Some("{\"id\":1}"));
+
+        assert!(matches!(transport.send("{\"id\":2}".to_string()).await, Err(McpError::ConnectionError(_))));
+        assert!(matches!(receive(&transport).await, Err(McpError::ConnectionError(_))));
+        assert!(matches!(transport.send("{\"id\":3}".to_string()).await, Err(McpError::Disconnected)));
+    }
+}
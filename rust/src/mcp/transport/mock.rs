@@ -0,0 +1,144 @@
+use crate::mcp::client::McpError;
+use crate::mcp::protocol::LATEST_PROTOCOL_VERSION;
+use crate::mcp::transport::Transport;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+type Result<T> = std::result::Result<T, McpError>;
+
+/// 模拟服务器对一条客户端消息的处理，返回推送给客户端的消息
+type Handler = Box<dyn Fn(&MockServer, &Value) -> Vec<Value> + Send + Sync>;
+
+/// 测试用的进程内模拟服务器，每次调用`transport`建立一个新连接
+#[derive(Clone)]
+pub(crate) struct MockServer {
+    state: Arc<MockState>,
+}
+
+struct MockState {
+    handler: Handler,
+    /// 客户端发出的所有消息
+    sent: Mutex<Vec<Value>>,
+    /// 各连接的推送端，连接断开后为None
+    connections: Mutex<Vec<Option<mpsc::UnboundedSender<String>>>>,
+}
+
+impl MockServer {
+    pub(crate) fn new(handler: impl Fn(&MockServer, &Value) -> Vec<Value> + Send + Sync + 'static) -> Self {
+        MockServer {
+            state: Arc::new(MockState {
+                handler: Box::new(handler),
+                sent: Mutex::new(Vec::new()),
+                connections: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// 建立新连接
+    pub(crate) fn transport(&self) -> MockTransport {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut connections = self.state.connections.lock().unwrap();
+        connections.push(Some(tx));
+        MockTransport {
+            server: self.clone(),
+            index: connections.len() - 1,
+            inbound: tokio::sync::Mutex::new(rx),
+        }
+    }
+
+    /// 已建立的连接数
+    pub(crate) fn connection_count(&self) -> usize {
+        self.state.connections.lock().unwrap().len()
+    }
+
+    /// 通过指定连接推送消息
+    fn push_to(&self, index: usize, message: Value) {
+        if let Some(Some(inbound)) = self.state.connections.lock().unwrap().get(index) {
+            let _ = inbound.send(message.to_string());
+        }
+    }
+
+    /// 通过最新的连接推送消息
+    pub(crate) fn push(&self, message: Value) {
+        self.push_to(self.connection_count() - 1, message);
+    }
+
+    /// 断开最新的连接，客户端的receive随之返回None
+    pub(crate) fn disconnect(&self) {
+        if let Some(inbound) = self.state.connections.lock().unwrap().last_mut() {
+            inbound.take();
+        }
+    }
+
+    /// 客户端发出的所有消息
+    pub(crate) fn sent(&self) -> Vec<Value> {
+        self.state.sent.lock().unwrap().clone()
+    }
+
+    /// 客户端发出的请求和通知的方法名
+    pub(crate) fn methods(&self) -> Vec<String> {
+        self.sent().iter().filter_map(|message| message["method"].as_str().map(str::to_string)).collect()
+    }
+}
+
+/// 模拟服务器的一个连接
+pub(crate) struct MockTransport {
+    server: MockServer,
+    /// 本连接的序号
+    index: usize,
+    inbound: tokio::sync::Mutex<mpsc::UnboundedReceiver<String>>,
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, frame: String) -> Result<()> {
+        let message: Value = serde_json::from_str(&frame).unwrap();
+        self.server.state.sent.lock().unwrap().push(message.clone());
+        for reply in (self.server.state.handler)(&self.server, &message) {
+            self.server.push_to(self.index, reply);
+        }
+        Ok(())
+    }
+
+    async fn receive(&self) -> Result<Option<String>> {
+        Ok(self.inbound.lock().await.recv().await)
+    }
+
+    async fn close(&self) -> Result<()> {
+        if let Some(inbound) = self.server.state.connections.lock().unwrap().get_mut(self.index) {
+            inbound.take();
+        }
+        Ok(())
+    }
+}
+
+/// 以请求的ID构造成功响应
+pub(crate) fn response(request: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+}
+
+/// 应答握手、ping和工具列表，其他请求不回复
+pub(crate) fn answer_defaults(request: &Value) -> Vec<Value> {
+    match request["method"].as_str() {
+        Some("initialize") => vec![response(request, json!({
+            "protocolVersion": LATEST_PROTOCOL_VERSION,
+            "capabilities": { "tools": { "listChanged": true }, "resources": { "subscribe": true } },
+            "serverInfo": { "name": "mock", "version": "1.0" }
+        }))],
+        Some("ping") => vec![response(request, json!({}))],
+        Some("tools/list") => vec![response(request, json!({ "tools": [] }))],
+        _ => Vec::new(),
+    }
+}
+
+/// 参数为任意对象的工具定义
+pub(crate) fn tool(name: &str) -> Value {
+    json!({ "name": name, "inputSchema": { "type": "object" } })
+}
+
+/// 只含一段文本的工具调用结果
+pub(crate) fn text_result(text: &str) -> Value {
+    json!({ "content": [{ "type": "text", "text": text }] })
+}
@@ -0,0 +1,134 @@
+mod http;
+#[cfg(test)]
+pub(crate) mod mock;
+mod stdio;
+mod websocket;
+
+pub use http::{HttpTransport, HttpTransportConfig};
+pub use stdio::{LogCallback, StdioTransport, StdioTransportConfig};
+pub use websocket::{WebSocketTransport, WebSocketTransportConfig};
+
+use crate::mcp::client::McpError;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, McpError>;
+
+/// 消息传输层抽象
+///
+/// 每一帧为一条完整的JSON消息，编解码由客户端负责
+#[async_trait]
+pub trait Transport: Send + Sync + 'static {
+    /// 发送一帧消息
+    async fn send(&self, frame: String) -> Result<()>;
+    
+    /// 接收下一帧消息，对端正常关闭时返回`None`
+    async fn receive(&self) -> Result<Option<String>>;
+    
+    /// 关闭传输
+    async fn close(&self) -> Result<()>;
+}
+
+#[async_trait]
+impl Transport for Box<dyn Transport> {
+    async fn send(&self, frame: String) -> Result<()> {
+        (**self).send(frame).await
+    }
+    
+    async fn receive(&self) -> Result<Option<String>> {
+        (**self).receive().await
+    }
+    
+    async fn close(&self) -> Result<()> {
+        (**self).close().await
+    }
+}
+
+/// 按地址连接时的附加选项
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectOptions {
+    /// HTTP请求或WebSocket握手附带的额外请求头 (如认证令牌)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 旧版HTTP+SSE模式的事件流地址，仅对HTTP地址有效
+    #[serde(default)]
+    pub sse_endpoint: Option<String>,
+    /// stdio服务器进程的启动参数，每项原样传给进程，不做拆分或转义
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// stdio服务器进程的额外环境变量
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// stdio服务器进程的工作目录
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+}
+
+/// 根据服务器地址创建对应的传输
+///
+/// 支持的地址格式:
+/// - `stdio:<可执行文件>` 启动本地服务器进程，参数、环境变量和工作目录由`options`指定
+/// - `ws://` / `wss://` 建立WebSocket连接
+/// - `http://` / `https://` 使用Streamable HTTP传输
+pub async fn connect_url(server_url: &str, options: &ConnectOptions) -> Result<Box<dyn Transport>> {
+    if server_url.starts_with("http://") || server_url.starts_with("https://") {
+        let mut config = HttpTransportConfig::new(server_url);
+        config.headers = options.headers.clone();
+        config.sse_endpoint = options.sse_endpoint.clone();
+        return Ok(Box::new(HttpTransport::connect(config).await?));
+    }
+    
+    if server_url.starts_with("ws://") || server_url.starts_with("wss://") {
+        let mut config = WebSocketTransportConfig::new(server_url);
+        config.headers = options.headers.clone();
+        return Ok(Box::new(WebSocketTransport::connect(config).await?));
+    }
+    
+    if let Some(command) = server_url.strip_prefix("stdio:") {
+        // 可执行文件路径可以包含空格，不拆分
+        let command = command.trim();
+        if command.is_empty() {
+            return Err(McpError::ConnectionError("stdio地址缺少可执行文件".to_string()));
+        }
+        
+        let mut config = StdioTransportConfig::new(command);
+        config.args = options.args.clone();
+        config.env = options.env.clone();
+        config.working_dir = options.working_dir.clone();
+        
+        return Ok(Box::new(StdioTransport::spawn(config, None)?));
+    }
+    
+    Err(McpError::ConnectionError(format!("不支持的服务器地址: {}", server_url)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stdio_url_passes_args_and_env_unsplit() {
+        let options = ConnectOptions {
+            args: vec!["-c".to_string(), "echo \"$GREETING\"".to_string()],
+            env: HashMap::from([("GREETING".to_string(), "hello world".to_string())]),
+            ..ConnectOptions::default()
+        };
+
+        let transport = connect_url("stdio:sh", &options).await.unwrap();
+        assert_eq!(transport.receive().await.unwrap().as_deref(), Some("hello world"));
+        assert_eq!(transport.receive().await.unwrap(), None);
+    }
+
+    #[test]
+    fn connect_options_accept_stdio_fields() {
+        let options: ConnectOptions = serde_json::from_str(
+            r#"{"args": ["--port", "0"], "env": {"TOKEN": "t"}, "workingDir": "/tmp"}"#,
+        ).unwrap();
+        assert_eq!(options.args, ["--port", "0"]);
+        assert_eq!(options.env.get("TOKEN").map(String::as_str), Some("t"));
+        assert_eq!(options.working_dir, Some(PathBuf::from("/tmp")));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::mcp::client::McpError;
+use crate::mcp::transport::Transport;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+type Result<T> = std::result::Result<T, McpError>;
+
+/// 服务器进程标准错误输出的日志回调
+pub type LogCallback = Arc<dyn Fn(String) + Send + Sync>;
+
+/// 本地服务器进程配置
+#[derive(Debug, Clone)]
+pub struct StdioTransportConfig {
+    /// 可执行文件路径
+    pub command: String,
+    /// 启动参数
+    pub args: Vec<String>,
+    /// 额外的环境变量
+    pub env: HashMap<String, String>,
+    /// 工作目录 (可选)
+    pub working_dir: Option<PathBuf>,
+    /// 关闭时等待进程自行退出的时间，超时后强制终止
+    pub shutdown_timeout: Duration,
+}
+
+impl StdioTransportConfig {
+    /// 创建新的进程配置
+    pub fn new(command: &str) -> Self {
+        StdioTransportConfig {
+            command: command.to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            working_dir: None,
+            shutdown_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+/// 基于子进程标准输入输出的传输
+///
+/// 每条消息占一行 (换行分隔的JSON)，子进程的标准错误输出逐行转发到日志回调
+pub struct StdioTransport {
+    /// 子进程句柄
+    child: Mutex<Child>,
+    /// 子进程标准输入 (关闭后为None)
+    stdin: Mutex<Option<ChildStdin>>,
+    /// 子进程标准输出
+    stdout: Mutex<Lines<BufReader<ChildStdout>>>,
+    /// 关闭时等待进程自行退出的时间
+    shutdown_timeout: Duration,
+}
+
+impl StdioTransport {
+    /// 启动服务器进程并建立传输
+    ///
+    /// 必须在tokio运行时中调用
+    pub fn spawn(config: StdioTransportConfig, log_callback: Option<LogCallback>) -> Result<Self> {
+        let mut command = Command::new(&config.command);
+        command
+            .args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(dir) = &config.working_dir {
+            command.current_dir(dir);
+        }
+        
+        let mut child = command.spawn()
+            .map_err(|e| McpError::ConnectionError(format!("启动服务器进程失败 {}: {}", config.command, e)))?;
+        
+        let stdin = child.stdin.take()
+            .ok_or_else(|| McpError::ConnectionError("无法获取进程标准输入".to_string()))?;
+        let stdout = child.stdout.take()
+            .ok_or_else(|| McpError::ConnectionError("无法获取进程标准输出".to_string()))?;
+        
+        // 转发标准错误输出
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    match &log_callback {
+                        Some(callback) => callback(line),
+                        None => log::info!(target: "mcp::server", "{}", line),
+                    }
+                }
+            });
+        }
+        
+        Ok(StdioTransport {
+            child: Mutex::new(child),
+            stdin: Mutex::new(Some(stdin)),
+            stdout: Mutex::new(BufReader::new(stdout).lines()),
+            shutdown_timeout: config.shutdown_timeout,
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for StdioTransport {
+    async fn send(&self, frame: String) -> Result<()> {
+        let mut stdin = self.stdin.lock().await;
+        let stdin = stdin.as_mut().ok_or(McpError::Disconnected)?;
+        
+        let result = async {
+            stdin.write_all(frame.as_bytes()).await?;
+            stdin.write_all(b"\n").await?;
+            stdin.flush().await
+        }.await;
+        
+        // 写入失败时请求未送达服务器
+        result.map_err(|e| McpError::ConnectionError(format!("写入进程标准输入失败: {}", e)))
+    }
+    
+    async fn receive(&self) -> Result<Option<String>> {
+        let mut stdout = self.stdout.lock().await;
+        loop {
+            match stdout.next_line().await {
+                // 跳过空行
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(line) => return Ok(line),
+                Err(e) => return Err(McpError::CommunicationError(format!("读取进程标准输出失败: {}", e))),
+            }
+        }
+    }
+    
+    async fn close(&self) -> Result<()> {
+        // 先关闭标准输入，让服务器有机会自行退出
+        self.stdin.lock().await.take();
+        
+        let mut child = self.child.lock().await;
+        if let Ok(Ok(_)) = tokio::time::timeout(self.shutdown_timeout, child.wait()).await {
+            return Ok(());
+        }
+        
+        // 超时仍未退出时强制终止
+        child.kill().await
+            .map_err(|e| McpError::InternalError(format!("终止服务器进程失败: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn shell(script: &str) -> StdioTransportConfig {
+        let mut config = StdioTransportConfig::new("sh");
+        config.args = vec!["-c".to_string(), script.to_string()];
+        config
+    }
+
+    #[tokio::test]
+    async fn exchanges_newline_delimited_messages() {
+        let transport = StdioTransport::spawn(StdioTransportConfig::new("cat"), None).unwrap();
+
+        transport.send("{\"id\":1}".to_string()).await.unwrap();
+        // 空行被跳过
+        transport.send(String::new()).await.unwrap();
+        transport.send("{\"id\":2}".to_string()).await.unwrap();
+
+        assert_eq!(transport.receive().await.unwrap().as_deref(), Some("{\"id\":1}"));
+        assert_eq!(transport.receive().await.unwrap().as_deref(), Some("{\"id\":2}"));
+
+        // 关闭标准输入后cat自行退出，标准输出随之结束
+        transport.close().await.unwrap();
+        assert_eq!(transport.receive().await.unwrap(), None);
+        assert!(matches!(transport.send("{}".to_string()).await, Err(McpError::Disconnected)));
+    }
+
+    #[tokio::test]
+    async fn forwards_stderr_lines_to_log_callback() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let log_callback: LogCallback = Arc::new(move |line| {
+            let _ = tx.send(line);
+        });
+
+        let transport = StdioTransport::spawn(shell("echo starting >&2; echo ready >&2; cat"), Some(log_callback)).unwrap();
+
+        assert_eq!(rx.recv().await.as_deref(), Some("starting"));
+        assert_eq!(rx.recv().await.as_deref(), Some("ready"));
+        transport.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn close_kills_child_after_shutdown_timeout() {
+        // 进程不读取标准输入，关闭输入后不会自行退出
+        let mut config = shell("sleep 30");
+        config.shutdown_timeout = Duration::from_millis(100);
+        let transport = StdioTransport::spawn(config, None).unwrap();
+
+        let started = std::time::Instant::now();
+        transport.close().await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(started.elapsed() >= Duration::from_millis(100));
+
+        let status = transport.child.lock().await.try_wait().unwrap();
+        assert!(status.is_some(), "进程应已被终止");
+    }
+}
@@ -0,0 +1,345 @@
+use crate::mcp::client::McpError;
+use crate::mcp::transport::Transport;
+use async_trait::async_trait;
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Result<T> = std::result::Result<T, McpError>;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// WebSocket连接配置
+#[derive(Debug, Clone)]
+pub struct WebSocketTransportConfig {
+    /// 服务器地址 (ws://或wss://)
+    pub url: String,
+    /// 握手请求附带的额外请求头 (如认证令牌)
+    pub headers: HashMap<String, String>,
+    /// 心跳间隔，为None时不发送ping
+    pub ping_interval: Option<Duration>,
+    /// 超过该时长未收到pong则视为连接已失效
+    pub pong_timeout: Duration,
+}
+
+impl WebSocketTransportConfig {
+    /// 创建新的连接配置
+    pub fn new(url: &str) -> Self {
+        WebSocketTransportConfig {
+            url: url.to_string(),
+            headers: HashMap::new(),
+            ping_interval: Some(Duration::from_secs(30)),
+            pong_timeout: Duration::from_secs(90),
+        }
+    }
+}
+
+/// 基于WebSocket文本帧的传输
+pub struct WebSocketTransport {
+    /// 写入端
+    sink: Arc<Mutex<SplitSink<WsStream, Message>>>,
+    /// 读取端
+    stream: Mutex<SplitStream<WsStream>>,
+    /// 最近一次收到pong的时间
+    last_pong: Arc<std::sync::Mutex<Instant>>,
+    /// 心跳超时通知
+    keepalive_failed: Arc<Notify>,
+    /// 心跳任务
+    keepalive: Option<JoinHandle<()>>,
+    /// 是否已主动关闭
+    closed: AtomicBool,
+    /// 主动关闭通知，唤醒等待中的receive (对端无响应时收不到关闭帧的回复)
+    close_requested: Notify,
+}
+
+impl WebSocketTransport {
+    /// 建立WebSocket连接
+    pub async fn connect(config: WebSocketTransportConfig) -> Result<Self> {
+        let mut request = config.url.as_str().into_client_request()
+            .map_err(|e| McpError::ConnectionError(format!("无效的WebSocket地址 {}: {}", config.url, e)))?;
+
+        for (name, value) in &config.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| McpError::ConnectionError(format!("无效的请求头名称 {}: {}", name, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| McpError::ConnectionError(format!("无效的请求头值: {}", e)))?;
+            request.headers_mut().insert(name, value);
+        }
+
+        let (ws_stream, _response) = tokio_tungstenite::connect_async(request).await
+            .map_err(|e| McpError::ConnectionError(format!("WebSocket握手失败: {}", e)))?;
+        let (sink, stream) = ws_stream.split();
+
+        let sink = Arc::new(Mutex::new(sink));
+        let last_pong = Arc::new(std::sync::Mutex::new(Instant::now()));
+        let keepalive_failed = Arc::new(Notify::new());
+
+        let keepalive = config.ping_interval.map(|interval| {
+            tokio::spawn(run_keepalive(
+                sink.clone(),
+                last_pong.clone(),
+                keepalive_failed.clone(),
+                interval,
+                config.pong_timeout,
+            ))
+        });
+
+        Ok(WebSocketTransport {
+            sink,
+            stream: Mutex::new(stream),
+            last_pong,
+            keepalive_failed,
+            keepalive,
+            closed: AtomicBool::new(false),
+            close_requested: Notify::new(),
+        })
+    }
+}
+
+/// 定期发送ping，超时未收到pong时发出通知
+async fn run_keepalive(
+    sink: Arc<Mutex<SplitSink<WsStream, Message>>>,
+    last_pong: Arc<std::sync::Mutex<Instant>>,
+    keepalive_failed: Arc<Notify>,
+    interval: Duration,
+    pong_timeout: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    // 第一次tick立即返回，跳过
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        if last_pong.lock().unwrap().elapsed() > pong_timeout {
+            keepalive_failed.notify_one();
+            return;
+        }
+
+        if sink.lock().await.send(Message::Ping(Vec::new().into())).await.is_err() {
+            keepalive_failed.notify_one();
+            return;
+        }
+    }
+}
+
+/// 将关闭帧映射为客户端错误
+fn close_error(frame: Option<CloseFrame>) -> McpError {
+    match frame {
+        None => McpError::Disconnected,
+        Some(frame) => match frame.code {
+            CloseCode::Normal | CloseCode::Away => McpError::Disconnected,
+            code => McpError::ConnectionError(format!("连接被关闭 [{}]: {}", u16::from(code), frame.reason)),
+        },
+    }
+}
+
+/// 将WebSocket错误映射为客户端错误
+fn ws_error(error: WsError) -> McpError {
+    match error {
+        WsError::ConnectionClosed | WsError::AlreadyClosed => McpError::Disconnected,
+        e => McpError::CommunicationError(format!("WebSocket通信失败: {}", e)),
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    async fn send(&self, frame: String) -> Result<()> {
+        // 写入失败时帧未送达服务器
+        self.sink.lock().await.send(Message::text(frame)).await.map_err(|e| match ws_error(e) {
+            McpError::CommunicationError(message) => McpError::ConnectionError(message),
+            error => error,
+        })
+    }
+
+    async fn receive(&self) -> Result<Option<String>> {
+        let mut stream = self.stream.lock().await;
+        loop {
+            if self.closed.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+
+            let message = tokio::select! {
+                message = stream.next() => message,
+                _ = self.close_requested.notified() => return Ok(None),
+                _ = self.keepalive_failed.notified() => {
+                    return Err(McpError::ConnectionError("心跳超时，连接已失效".to_string()));
+                }
+            };
+
+            match message {
+                None => return Ok(None),
+                Some(Err(e)) => return Err(ws_error(e)),
+                Some(Ok(Message::Text(text))) => return Ok(Some(text.to_string())),
+                Some(Ok(Message::Binary(data))) => {
+                    // 部分服务器使用二进制帧发送UTF-8编码的JSON
+                    return String::from_utf8(data.to_vec())
+                        .map(Some)
+                        .map_err(|_| McpError::ProtocolError("收到无法解码的二进制帧".to_string()));
+                }
+                Some(Ok(Message::Pong(_))) => {
+                    *self.last_pong.lock().unwrap() = Instant::now();
+                }
+                Some(Ok(Message::Close(frame))) => return Err(close_error(frame)),
+                // ping由底层自动回复pong
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+
+    async fn close(&self) -> Result<()> {
+        if let Some(keepalive) = &self.keepalive {
+            keepalive.abort();
+        }
+
+        // 不等待对端回复关闭帧，立即结束进行中的receive
+        self.closed.store(true, Ordering::SeqCst);
+        self.close_requested.notify_one();
+
+        let mut sink = self.sink.lock().await;
+        let close_frame = CloseFrame {
+            code: CloseCode::Normal,
+            reason: "".into(),
+        };
+
+        match sink.send(Message::Close(Some(close_frame))).await {
+            Ok(_) | Err(WsError::ConnectionClosed) | Err(WsError::AlreadyClosed) => Ok(()),
+            Err(e) => Err(ws_error(e)),
+        }
+    }
+}
+
+impl Drop for WebSocketTransport {
+    fn drop(&mut self) {
+        if let Some(keepalive) = &self.keepalive {
+            keepalive.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use tokio::net::TcpListener;
+
+    /// 启动进程内WebSocket服务器，由handler处理第一个连接，返回服务器地址
+    async fn spawn_server<F, Fut>(handler: F) -> String
+    where
+        F: FnOnce(WebSocketStream<TcpStream>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            handler(ws).await;
+        });
+        format!("ws://{}", address)
+    }
+
+    fn config(url: &str) -> WebSocketTransportConfig {
+        WebSocketTransportConfig {
+            ping_interval: None,
+            ..WebSocketTransportConfig::new(url)
+        }
+    }
+
+    /// 保持连接但不读取任何帧，模拟无响应的对端
+    async fn hang(ws: WebSocketStream<TcpStream>) {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        drop(ws);
+    }
+
+    #[tokio::test]
+    async fn sends_and_receives_text_and_binary_frames() {
+        let url = spawn_server(|mut ws| async move {
+            while let Some(Ok(message)) = ws.next().await {
+                if let Message::Text(text) = message {
+                    ws.send(Message::text(text.to_string())).await.unwrap();
+                    ws.send(Message::binary(b"{\"binary\":true}".to_vec())).await.unwrap();
+                }
+            }
+        }).await;
+
+        let transport = WebSocketTransport::connect(config(&url)).await.unwrap();
+        transport.send("{\"ping\":1}".to_string()).await.unwrap();
+
+        assert_eq!(transport.receive().await.unwrap().as_deref(), Some("{\"ping\":1}"));
+        assert_eq!(transport.receive().await.unwrap().as_deref(), Some("{\"binary\":true}"));
+    }
+
+    #[tokio::test]
+    async fn keepalive_timeout_fails_receive() {
+        let url = spawn_server(hang).await;
+
+        let config = WebSocketTransportConfig {
+            ping_interval: Some(Duration::from_millis(50)),
+            pong_timeout: Duration::from_millis(120),
+            ..WebSocketTransportConfig::new(&url)
+        };
+        let transport = WebSocketTransport::connect(config).await.unwrap();
+
+        let result = tokio::time::timeout(Duration::from_secs(2), transport.receive()).await
+            .expect("心跳超时后receive应返回");
+        assert!(matches!(result, Err(McpError::ConnectionError(_))));
+    }
+
+    #[tokio::test]
+    async fn normal_close_frame_maps_to_disconnected() {
+        let url = spawn_server(|mut ws| async move {
+            ws.close(Some(CloseFrame { code: CloseCode::Normal, reason: "".into() })).await.unwrap();
+        }).await;
+
+        let transport = WebSocketTransport::connect(config(&url)).await.unwrap();
+        assert!(matches!(transport.receive().await, Err(McpError::Disconnected)));
+    }
+
+    #[tokio::test]
+    async fn error_close_frame_maps_to_connection_error() {
+        let url = spawn_server(|mut ws| async move {
+            ws.close(Some(CloseFrame { code: CloseCode::Policy, reason: "unauthorized".into() })).await.unwrap();
+        }).await;
+
+        let transport = WebSocketTransport::connect(config(&url)).await.unwrap();
+        match transport.receive().await {
+            Err(McpError::ConnectionError(message)) => {
+                assert!(message.contains("1008"));
+                assert!(message.contains("unauthorized"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn close_wakes_pending_receive() {
+        let url = spawn_server(hang).await;
+
+        let transport = Arc::new(WebSocketTransport::connect(config(&url)).await.unwrap());
+        let receiver = tokio::spawn({
+            let transport = transport.clone();
+            async move { transport.receive().await }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        transport.close().await.unwrap();
+
+        let result = tokio::time::timeout(Duration::from_secs(1), receiver).await
+            .expect("关闭后receive应立即返回")
+            .unwrap();
+        assert!(matches!(result, Ok(None)));
+    }
+}
@@ -0,0 +1,548 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// URI模板错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct UriTemplateError {
+    /// 出错位置 (模板中的字符偏移)
+    pub position: usize,
+    /// 错误说明
+    pub message: String,
+}
+
+impl fmt::Display for UriTemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "URI模板错误 (位置 {}): {}", self.position, self.message)
+    }
+}
+
+impl Error for UriTemplateError {}
+
+type Result<T> = std::result::Result<T, UriTemplateError>;
+
+/// 表达式运算符的展开规则 (RFC 6570 附录A)
+struct Operator {
+    first: &'static str,
+    separator: &'static str,
+    named: bool,
+    if_empty: &'static str,
+    allow_reserved: bool,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Operator> {
+        let (first, separator, named, if_empty, allow_reserved) = match c {
+            '+' => ("", ",", false, "", true),
+            '#' => ("#", ",", false, "", true),
+            '.' => (".", ".", false, "", false),
+            '/' => ("/", "/", false, "", false),
+            ';' => (";", ";", true, "", false),
+            '?' => ("?", "&", true, "=", false),
+            '&' => ("&", "&", true, "=", false),
+            _ => return None,
+        };
+        Some(Operator { first, separator, named, if_empty, allow_reserved })
+    }
+
+    fn simple() -> Operator {
+        Operator { first: "", separator: ",", named: false, if_empty: "", allow_reserved: false }
+    }
+}
+
+/// 变量说明符
+struct VarSpec {
+    name: String,
+    prefix: Option<usize>,
+    explode: bool,
+}
+
+/// 模板中的一段
+enum Part {
+    Literal(String),
+    /// position为表达式左花括号在模板中的偏移
+    Expression { position: usize, operator: Operator, variables: Vec<VarSpec> },
+}
+
+/// 按RFC 6570 (Level 4) 展开URI模板
+///
+/// 字符串、数字和布尔值作为单个值展开，数组作为列表，对象作为键值对；
+/// null、空数组和空对象视为未定义的变量
+pub fn expand_uri_template(template: &str, variables: &HashMap<String, Value>) -> Result<String> {
+    let mut output = String::new();
+
+    for part in parse(template)? {
+        match part {
+            Part::Literal(text) => encode_into(&mut output, &text, true),
+            Part::Expression { position, operator, variables: specs } => {
+                expand_expression(&mut output, position, &operator, &specs, variables)?;
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// 列出模板中出现的变量名 (按出现顺序去重)
+pub fn template_variables(template: &str) -> Result<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    for part in parse(template)? {
+        if let Part::Expression { variables, .. } = part {
+            for spec in variables {
+                if !names.contains(&spec.name) {
+                    names.push(spec.name);
+                }
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn error(position: usize, message: &str) -> UriTemplateError {
+    UriTemplateError {
+        position,
+        message: message.to_string(),
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Part>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.char_indices();
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '{' => {
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+
+                let mut body = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, c)) => body.push(c),
+                        None => return Err(error(position, "表达式缺少右花括号")),
+                    }
+                }
+                parts.push(parse_expression(position, &body)?);
+            }
+            '}' => return Err(error(position, "多余的右花括号")),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    Ok(parts)
+}
+
+fn parse_expression(position: usize, body: &str) -> Result<Part> {
+    let (operator, list) = match body.chars().next().and_then(Operator::from_char) {
+        Some(operator) => (operator, &body[1..]),
+        None => match body.chars().next() {
+            // 保留给将来扩展的运算符
+            Some('=' | ',' | '!' | '@' | '|') => return Err(error(position, "不支持的运算符")),
+            _ => (Operator::simple(), body),
+        },
+    };
+
+    let mut variables = Vec::new();
+    for spec in list.split(',') {
+        let (name, prefix, explode) = if let Some(name) = spec.strip_suffix('*') {
+            (name, None, true)
+        } else if let Some((name, length)) = spec.split_once(':') {
+            let length: usize = length.parse()
+                .ok()
+                .filter(|length| (1..10000).contains(length))
+                .ok_or_else(|| error(position, "无效的前缀长度"))?;
+            (name, Some(length), false)
+        } else {
+            (spec, None, false)
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '%')) {
+            return Err(error(position, &format!("无效的变量名: {}", spec)));
+        }
+
+        variables.push(VarSpec {
+            name: name.to_string(),
+            prefix,
+            explode,
+        });
+    }
+
+    Ok(Part::Expression { position, operator, variables })
+}
+
+fn expand_expression(
+    output: &mut String,
+    position: usize,
+    operator: &Operator,
+    specs: &[VarSpec],
+    variables: &HashMap<String, Value>,
+) -> Result<()> {
+    let mut first = true;
+
+    for spec in specs {
+        let value = match variables.get(&spec.name) {
+            Some(value) if is_defined(value) => value,
+            _ => continue,
+        };
+
+        output.push_str(if first { operator.first } else { operator.separator });
+        first = false;
+
+        match value {
+            Value::Array(items) => {
+                if spec.prefix.is_some() {
+                    return Err(error(position, &format!("列表变量{}不能使用前缀修饰", spec.name)));
+                }
+                let items: Vec<String> = items.iter().filter_map(scalar_to_string).collect();
+                expand_list(output, operator, spec, &items);
+            }
+            Value::Object(map) => {
+                if spec.prefix.is_some() {
+                    return Err(error(position, &format!("键值对变量{}不能使用前缀修饰", spec.name)));
+                }
+                let pairs: Vec<(String, String)> = map.iter()
+                    .filter_map(|(key, value)| scalar_to_string(value).map(|value| (key.clone(), value)))
+                    .collect();
+                expand_pairs(output, operator, spec, &pairs);
+            }
+            scalar => {
+                let mut text = scalar_to_string(scalar).unwrap_or_default();
+                if let Some(prefix) = spec.prefix {
+                    text = text.chars().take(prefix).collect();
+                }
+
+                if operator.named {
+                    output.push_str(&spec.name);
+                    if text.is_empty() {
+                        output.push_str(operator.if_empty);
+                        continue;
+                    }
+                    output.push('=');
+                }
+                encode_into(output, &text, operator.allow_reserved);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn expand_list(output: &mut String, operator: &Operator, spec: &VarSpec, items: &[String]) {
+    if !spec.explode && operator.named {
+        output.push_str(&spec.name);
+        output.push('=');
+    }
+
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            output.push_str(if spec.explode { operator.separator } else { "," });
+        }
+        if spec.explode && operator.named {
+            output.push_str(&spec.name);
+            if item.is_empty() {
+                output.push_str(operator.if_empty);
+                continue;
+            }
+            output.push('=');
+        }
+        encode_into(output, item, operator.allow_reserved);
+    }
+}
+
+fn expand_pairs(output: &mut String, operator: &Operator, spec: &VarSpec, pairs: &[(String, String)]) {
+    if !spec.explode && operator.named {
+        output.push_str(&spec.name);
+        output.push('=');
+    }
+
+    for (index, (key, value)) in pairs.iter().enumerate() {
+        if index > 0 {
+            output.push_str(if spec.explode { operator.separator } else { "," });
+        }
+        encode_into(output, key, operator.allow_reserved);
+        if spec.explode {
+            if operator.named && value.is_empty() {
+                output.push_str(operator.if_empty);
+                continue;
+            }
+            output.push('=');
+        } else {
+            output.push(',');
+        }
+        encode_into(output, value, operator.allow_reserved);
+    }
+}
+
+fn is_defined(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Array(items) => items.iter().any(|item| scalar_to_string(item).is_some()),
+        Value::Object(map) => map.values().any(|value| scalar_to_string(value).is_some()),
+        _ => true,
+    }
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+fn is_reserved(c: char) -> bool {
+    matches!(c, ':' | '/' | '?' | '#' | '[' | ']' | '@' | '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '=')
+}
+
+/// 百分号编码，allow_reserved为true时保留保留字符和已编码的三元组
+fn encode_into(output: &mut String, text: &str, allow_reserved: bool) {
+    let bytes = text.as_bytes();
+    for (index, c) in text.char_indices() {
+        let keep = is_unreserved(c)
+            || (allow_reserved && is_reserved(c))
+            || (allow_reserved
+                && c == '%'
+                && bytes.get(index + 1).is_some_and(u8::is_ascii_hexdigit)
+                && bytes.get(index + 2).is_some_and(u8::is_ascii_hexdigit));
+
+        if keep {
+            output.push(c);
+        } else {
+            let mut buffer = [0u8; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                output.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// RFC 6570 第3.2节示例使用的变量 (keys按字母顺序书写，与Map的实现无关)
+    fn rfc_variables() -> HashMap<String, Value> {
+        let variables = json!({
+            "count": ["one", "two", "three"],
+            "dom": ["example", "com"],
+            "dub": "me/too",
+            "hello": "Hello World!",
+            "half": "50%",
+            "var": "value",
+            "who": "fred",
+            "base": "http://example.com/home/",
+            "path": "/foo/bar",
+            "list": ["red", "green", "blue"],
+            "keys": { "comma": ",", "dot": ".", "semi": ";" },
+            "v": "6",
+            "x": "1024",
+            "y": "768",
+            "empty": "",
+            "empty_keys": {},
+            "undef": null,
+        });
+        serde_json::from_value(variables).unwrap()
+    }
+
+    fn assert_expansions(cases: &[(&str, &str)]) {
+        let variables = rfc_variables();
+        for (template, expected) in cases {
+            assert_eq!(expand_uri_template(template, &variables).unwrap(), *expected, "模板: {}", template);
+        }
+    }
+
+    #[test]
+    fn expands_simple_strings() {
+        assert_expansions(&[
+            ("{var}", "value"),
+            ("{hello}", "Hello%20World%21"),
+            ("{half}", "50%25"),
+            ("O{empty}X", "OX"),
+            ("O{undef}X", "OX"),
+            ("{x,y}", "1024,768"),
+            ("{x,hello,y}", "1024,Hello%20World%21,768"),
+            ("?{x,empty}", "?1024,"),
+            ("?{x,undef}", "?1024"),
+            ("?{undef,y}", "?768"),
+            ("{var:3}", "val"),
+            ("{var:30}", "value"),
+            ("{list}", "red,green,blue"),
+            ("{list*}", "red,green,blue"),
+            ("{keys}", "comma,%2C,dot,.,semi,%3B"),
+            ("{keys*}", "comma=%2C,dot=.,semi=%3B"),
+        ]);
+    }
+
+    #[test]
+    fn expands_reserved_strings() {
+        assert_expansions(&[
+            ("{+var}", "value"),
+            ("{+hello}", "Hello%20World!"),
+            ("{+half}", "50%25"),
+            ("{base}index", "http%3A%2F%2Fexample.com%2Fhome%2Findex"),
+            ("{+base}index", "http://example.com/home/index"),
+            ("O{+empty}X", "OX"),
+            ("{+path}/here", "/foo/bar/here"),
+            ("here?ref={+path}", "here?ref=/foo/bar"),
+            ("up{+path}{var}/here", "up/foo/barvalue/here"),
+            ("{+x,hello,y}", "1024,Hello%20World!,768"),
+            ("{+path,x}/here", "/foo/bar,1024/here"),
+            ("{+path:6}/here", "/foo/b/here"),
+            ("{+list}", "red,green,blue"),
+            ("{+keys}", "comma,,,dot,.,semi,;"),
+            ("{+keys*}", "comma=,,dot=.,semi=;"),
+        ]);
+    }
+
+    #[test]
+    fn expands_fragments() {
+        assert_expansions(&[
+            ("{#var}", "#value"),
+            ("{#hello}", "#Hello%20World!"),
+            ("{#half}", "#50%25"),
+            ("foo{#empty}", "foo#"),
+            ("foo{#undef}", "foo"),
+            ("{#x,hello,y}", "#1024,Hello%20World!,768"),
+            ("{#path,x}/here", "#/foo/bar,1024/here"),
+            ("{#path:6}/here", "#/foo/b/here"),
+            ("{#list}", "#red,green,blue"),
+            ("{#list*}", "#red,green,blue"),
+            ("{#keys*}", "#comma=,,dot=.,semi=;"),
+        ]);
+    }
+
+    #[test]
+    fn expands_labels() {
+        assert_expansions(&[
+            ("{.who}", ".fred"),
+            ("{.who,who}", ".fred.fred"),
+            ("{.half,who}", ".50%25.fred"),
+            ("www{.dom*}", "www.example.com"),
+            ("X{.var}", "X.value"),
+            ("X{.empty}", "X."),
+            ("X{.undef}", "X"),
+            ("X{.var:3}", "X.val"),
+            ("X{.list}", "X.red,green,blue"),
+            ("X{.list*}", "X.red.green.blue"),
+            ("X{.keys}", "X.comma,%2C,dot,.,semi,%3B"),
+            ("X{.keys*}", "X.comma=%2C.dot=..semi=%3B"),
+            ("X{.empty_keys}", "X"),
+        ]);
+    }
+
+    #[test]
+    fn expands_path_segments() {
+        assert_expansions(&[
+            ("{/who}", "/fred"),
+            ("{/who,who}", "/fred/fred"),
+            ("{/half,who}", "/50%25/fred"),
+            ("{/who,dub}", "/fred/me%2Ftoo"),
+            ("{/var}", "/value"),
+            ("{/var,empty}", "/value/"),
+            ("{/var,undef}", "/value"),
+            ("{/var,x}/here", "/value/1024/here"),
+            ("{/var:1,var}", "/v/value"),
+            ("{/list}", "/red,green,blue"),
+            ("{/list*}", "/red/green/blue"),
+            ("{/list*,path:4}", "/red/green/blue/%2Ffoo"),
+            ("{/keys*}", "/comma=%2C/dot=./semi=%3B"),
+        ]);
+    }
+
+    #[test]
+    fn expands_path_parameters() {
+        assert_expansions(&[
+            ("{;who}", ";who=fred"),
+            ("{;half}", ";half=50%25"),
+            ("{;empty}", ";empty"),
+            ("{;v,empty,who}", ";v=6;empty;who=fred"),
+            ("{;v,bar,who}", ";v=6;who=fred"),
+            ("{;x,y}", ";x=1024;y=768"),
+            ("{;x,y,empty}", ";x=1024;y=768;empty"),
+            ("{;x,y,undef}", ";x=1024;y=768"),
+            ("{;hello:5}", ";hello=Hello"),
+            ("{;list}", ";list=red,green,blue"),
+            ("{;list*}", ";list=red;list=green;list=blue"),
+            ("{;keys}", ";keys=comma,%2C,dot,.,semi,%3B"),
+            ("{;keys*}", ";comma=%2C;dot=.;semi=%3B"),
+        ]);
+    }
+
+    #[test]
+    fn expands_queries() {
+        assert_expansions(&[
+            ("{?who}", "?who=fred"),
+            ("{?half}", "?half=50%25"),
+            ("{?x,y}", "?x=1024&y=768"),
+            ("{?x,y,empty}", "?x=1024&y=768&empty="),
+            ("{?x,y,undef}", "?x=1024&y=768"),
+            ("{?var:3}", "?var=val"),
+            ("{?list}", "?list=red,green,blue"),
+            ("{?list*}", "?list=red&list=green&list=blue"),
+            ("{?keys}", "?keys=comma,%2C,dot,.,semi,%3B"),
+            ("{?keys*}", "?comma=%2C&dot=.&semi=%3B"),
+            ("{&who}", "&who=fred"),
+            ("{&half}", "&half=50%25"),
+            ("?fixed=yes{&x}", "?fixed=yes&x=1024"),
+            ("{&x,y,empty}", "&x=1024&y=768&empty="),
+            ("{&var:3}", "&var=val"),
+            ("{&list}", "&list=red,green,blue"),
+            ("{&list*}", "&list=red&list=green&list=blue"),
+            ("{&keys}", "&keys=comma,%2C,dot,.,semi,%3B"),
+            ("{&keys*}", "&comma=%2C&dot=.&semi=%3B"),
+        ]);
+    }
+
+    #[test]
+    fn percent_encodes_values() {
+        let variables: HashMap<String, Value> = serde_json::from_value(json!({
+            "name": "résumé 1",
+            "encoded": "a%20b",
+            "number": 3,
+            "flag": true,
+        })).unwrap();
+
+        let expand = |template: &str| expand_uri_template(template, &variables).unwrap();
+        assert_eq!(expand("{name}"), "r%C3%A9sum%C3%A9%201");
+        // 只有保留展开会保留已编码的三元组
+        assert_eq!(expand("{encoded}"), "a%2520b");
+        assert_eq!(expand("{+encoded}"), "a%20b");
+        assert_eq!(expand("{?number,flag}"), "?number=3&flag=true");
+        // 字面部分同样需要编码
+        assert_eq!(expand("file:///docs/{name} v2"), "file:///docs/r%C3%A9sum%C3%A9%201%20v2");
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let variables = rfc_variables();
+        let position = |template: &str| expand_uri_template(template, &variables).unwrap_err().position;
+
+        assert_eq!(position("a{var"), 1);
+        assert_eq!(position("ab}"), 2);
+        assert_eq!(position("x{=var}"), 1);
+        assert_eq!(position("x{var:0}"), 1);
+        assert_eq!(position("x{va r}"), 1);
+        // 列表和键值对变量不能使用前缀修饰，错误指向所在的表达式
+        assert_eq!(position("/a/{var}/{list:2}"), 9);
+        assert_eq!(position("/{+keys:1}"), 1);
+    }
+
+    #[test]
+    fn lists_template_variables() {
+        let names = template_variables("file:///{+path}{?query,page}{&query}").unwrap();
+        assert_eq!(names, vec!["path", "query", "page"]);
+    }
+}